@@ -11,8 +11,25 @@ mod erc20 {
         },
         collections::HashMap as StorageHashMap,
 
-        traits::SpreadAllocate,
+        traits::{
+            PackedLayout,
+            SpreadAllocate,
+            SpreadLayout,
+        },
+    };
+    use ink_env::{
+        call::{
+            build_call,
+            utils::ReturnType,
+            ExecutionInput,
+            Selector,
+        },
+        chain_extension::ChainExtensionMethod,
+        hash::Blake2x256,
+        DefaultEnvironment,
     };
+    use ink_primitives::Key;
+    use ink_prelude::collections::BTreeSet;
     use ink_prelude::string::String;
     use ink_prelude::vec::Vec;
 
@@ -35,7 +52,873 @@ mod erc20 {
         seats: Vec<String>,
         seat_taken: StorageHashMap<String,bool>,
         seat_balance:StorageHashMap<AccountId,bool>,
-        has_seats: bool
+        has_seats: bool,
+        /// Cumulative native funds paid in by each account.
+        total_paid: StorageHashMap<AccountId, Balance>,
+        /// Tier assigned to a seat. Seats without a tier are sold at `price`.
+        seat_tier: StorageHashMap<String, String>,
+        /// Price of a single seat in each tier.
+        tier_price: StorageHashMap<String, Balance>,
+        /// Gross revenue collected per tier.
+        tier_revenue: StorageHashMap<String, Balance>,
+        /// Names of all defined tiers, in the order they were created.
+        tiers: Vec<String>,
+        /// Current holder of each sold seat.
+        seat_owner: StorageHashMap<String, AccountId>,
+        /// Whether tickets are bound to their holder and can only be moved by verifiers.
+        soulbound: bool,
+        /// Marketplace contracts approved by the owner for resales.
+        marketplaces: StorageHashMap<AccountId, bool>,
+        /// Whether `transfer_from` and seat transfers are limited to approved marketplaces.
+        restricted_transfers: bool,
+        /// Number of signed payloads consumed per account, used for replay protection.
+        nonces: StorageHashMap<AccountId, u64>,
+        /// Admins that approve privileged actions once multisig control is enabled.
+        admins: Vec<AccountId>,
+        /// Number of admin confirmations an action needs. `0` disables multisig control.
+        admin_threshold: u32,
+        /// Proposed privileged actions by id.
+        proposals: StorageHashMap<u64, Proposal>,
+        /// Id of the next proposal.
+        next_proposal_id: u64,
+        /// Number of blocks between a withdrawal request and its execution.
+        withdraw_delay: BlockNumber,
+        /// Withdrawal waiting for its timelock to pass.
+        pending_withdrawal: Option<PendingWithdrawal>,
+        /// Version of the storage layout, see `migrate_storage`.
+        storage_version: u32,
+        /// Timestamp at which the event ends. `0` if not set.
+        event_end: Timestamp,
+        /// Whether the event has been wound down, which stops all sales and resales.
+        finalized: bool,
+        /// Unused, the reentrancy guard is written under `REENTRANCY_GUARD_KEY`. Kept so
+        /// the keys of the following fields do not shift.
+        entered: bool,
+        /// Price oracle converting fiat prices into native funds, see `set_oracle`.
+        oracle: Option<AccountId>,
+        /// Maximum age in milliseconds of the oracle rate accepted for purchases.
+        oracle_max_age: Timestamp,
+        /// Purchase record of each sold seat.
+        seat_purchases: StorageHashMap<String, SeatPurchase>,
+        /// Time in milliseconds after a purchase during which its buyer can raise a dispute.
+        dispute_window: Timestamp,
+        /// Funds frozen by open disputes, per disputed seat.
+        disputes: StorageHashMap<String, Balance>,
+        /// Sum of all funds frozen by open disputes. These cannot be withdrawn.
+        frozen_funds: Balance,
+        /// Accounts whose KYC has been attested by a verifier.
+        kyc_attested: StorageHashMap<AccountId, bool>,
+        /// Whether purchases are limited to KYC attested accounts.
+        kyc_required: bool,
+        /// Accounts blocked from purchasing, receiving and transferring tickets.
+        blacklist: StorageHashMap<AccountId, bool>,
+        /// Seats pulled out of sale by the owner.
+        blocked_seats: StorageHashMap<String, bool>,
+        /// General admission zones by name.
+        zones: StorageHashMap<String, Zone>,
+        /// Names of all zones, in the order they were created.
+        zone_names: Vec<String>,
+        /// Number of general admission tickets a seated contract sells next to its seats.
+        ga_capacity: u32,
+        /// Number of general admission tickets sold by a seated contract.
+        ga_sold: u32,
+        /// Price of a general admission ticket of a seated contract.
+        ga_price: Balance,
+        /// Current or last seat lottery.
+        lottery: Option<Lottery>,
+        /// Seats held back from regular sale for the lottery.
+        lottery_seats: StorageHashMap<String, bool>,
+        /// Entrants of the current lottery, in order of entry.
+        lottery_entrants: Vec<AccountId>,
+        /// Deposits of lottery entrants that have not been assigned a seat or refunded.
+        lottery_deposits: StorageHashMap<AccountId, Balance>,
+        /// Sum of all lottery deposits. These are not part of the contract balance.
+        lottery_escrow: Balance,
+        /// Descending-price auction for the seats in `dutch_seats`, if one is running.
+        dutch_auction: Option<DutchAuction>,
+        /// Seats sold at the current Dutch auction price.
+        dutch_seats: StorageHashMap<String, bool>,
+        /// Running English auctions by seat.
+        auctions: StorageHashMap<String, Auction>,
+        /// Sum of the highest bids of all running auctions. These are not part of the
+        /// contract balance.
+        auction_escrow: Balance,
+        /// Whether purchases may send more than the price as a tip.
+        accept_tips: bool,
+        /// Charity or artist account that receives tips.
+        tip_recipient: Option<AccountId>,
+        /// Sum of all tips forwarded to the tip recipient.
+        total_donations: Balance,
+        /// Length of a vesting period of the proceeds in milliseconds.
+        vesting_period: Timestamp,
+        /// Percentage of the proceeds released per vesting period, `0` if not vesting.
+        vesting_percent: u8,
+        /// Proceeds withdrawn by the owner so far.
+        vesting_released: Balance,
+        /// Time to live of a seat hold in milliseconds, `0` if holds are disabled.
+        hold_ttl: Timestamp,
+        /// Deposit per held seat.
+        hold_deposit: Balance,
+        /// Part of a hold deposit paid to whoever expires the hold.
+        hold_bounty: Balance,
+        /// Seat holds by seat. Expired holds stay until they are cleared.
+        holds: StorageHashMap<String, SeatHold>,
+        /// Sum of all hold deposits. These are not part of the contract balance.
+        hold_escrow: Balance,
+        /// Seats whose holder has been checked in.
+        checked_in: StorageHashMap<String, bool>,
+        /// Timestamp at which the contract was deployed.
+        deployed_at: Timestamp,
+        /// Explicit prices of individual seats, taking precedence over their tier price.
+        seat_price_override: StorageHashMap<String, Balance>,
+        /// Whether `SoldOut` has been emitted since tickets were last available.
+        sold_out: bool,
+        /// Tickets an account may buy per throttle window, `0` if not throttled.
+        throttle_limit: u32,
+        /// Length of a throttle window in blocks.
+        throttle_window: BlockNumber,
+        /// Throttle window of the last purchase of an account and the tickets bought in it.
+        purchase_windows: StorageHashMap<AccountId, (BlockNumber, u32)>,
+        /// Blocks a purchase commitment can be revealed in, `0` if commit–reveal is disabled.
+        reveal_window: BlockNumber,
+        /// Deposit every purchase commitment needs at least.
+        commit_min_deposit: Balance,
+        /// Purchase commitments by buyer.
+        commitments: StorageHashMap<AccountId, Commitment>,
+        /// Identifier of the next purchase commitment, giving the commit order.
+        next_commitment_id: u64,
+        /// Seats claimed by revealed commitments, with the commitment id and buyer.
+        seat_claims: StorageHashMap<String, (u64, AccountId)>,
+        /// Sum of all commitment deposits. These are not part of the contract balance.
+        commit_escrow: Balance,
+        /// Service fee charged on top of every purchase, in basis points.
+        fee_bps: u16,
+        /// Account allowed to withdraw the service fees.
+        fee_collector: Option<AccountId>,
+        /// Service fees not withdrawn yet. These are not part of the contract balance.
+        fees_accrued: Balance,
+        /// Total service fees collected.
+        fee_collected: Balance,
+        /// Streaming payout of the proceeds, if set.
+        stream: Option<Stream>,
+        /// Chain extension function checking buyer identities, if strict mode is on.
+        identity_extension: Option<u32>,
+        /// Spenders with a non-zero allowance, by owner.
+        approved_spenders: StorageHashMap<AccountId, Vec<AccountId>>,
+        /// Accounts with a non-zero balance.
+        holders: Vec<AccountId>,
+        /// Position of every holder in `holders`.
+        holder_index: StorageHashMap<AccountId, u32>,
+        /// Id of the latest balance snapshot, `0` if none was taken.
+        snapshot_id: u32,
+        /// Balances recorded for snapshots, by account, as `(snapshot_id, balance)` in
+        /// ascending snapshot order. A balance recorded for a snapshot is the balance at
+        /// that snapshot and all earlier ones without a record.
+        balance_snapshots: StorageHashMap<AccountId, Vec<(u32, Balance)>>,
+        /// Ticket-holder governance proposals by id.
+        governance_proposals: StorageHashMap<u64, GovernanceProposal>,
+        /// Id of the next governance proposal.
+        next_governance_id: u64,
+        /// Votes cast on governance proposals, by proposal id and voter.
+        votes: StorageHashMap<(u64, AccountId), bool>,
+        /// Timestamps after which verifier rights lapse, for time-limited verifiers.
+        verifier_expiry: StorageHashMap<AccountId, Timestamp>,
+        /// Bitmask of the scopes granted to each verifier. Verifiers without an entry
+        /// hold `ALL_SCOPES`.
+        verifier_scopes: StorageHashMap<AccountId, u8>,
+        /// Description of the event the tickets are for.
+        event_metadata: Option<EventMetadata>,
+        /// Timestamp at which the event starts and sales and resales stop.
+        event_start: Option<Timestamp>,
+        /// Time after `event_start` during which holders can still be checked in.
+        check_in_grace: Timestamp,
+        /// Accounts authorized by a company to confirm its purchase proposals.
+        purchase_signers: StorageHashMap<AccountId, Vec<AccountId>>,
+        /// Time a purchase proposal can be confirmed in. `0` disables proposals.
+        approval_window: Timestamp,
+        /// Purchase proposals awaiting confirmation, by id.
+        purchase_proposals: StorageHashMap<u64, PurchaseProposal>,
+        /// Id of the next purchase proposal.
+        next_purchase_proposal_id: u64,
+        /// Deposits of pending purchase proposals, kept apart from the contract balance.
+        proposal_escrow: Balance,
+        /// Seat reservations awaiting payment, by id.
+        invoices: StorageHashMap<u64, Invoice>,
+        /// Id of the next invoice.
+        next_invoice_id: u64,
+        /// Invoice each reserved seat belongs to.
+        invoiced_seats: StorageHashMap<String, u64>,
+        /// Number of installments a seat is paid in. `0` disables installment plans.
+        installment_count: u32,
+        /// Time in milliseconds between installment deadlines.
+        installment_interval: Timestamp,
+        /// Share in basis points of the paid installments forfeited on a missed deadline.
+        installment_forfeit_bps: u16,
+        /// Installment plans by seat.
+        installment_plans: StorageHashMap<String, InstallmentPlan>,
+        /// Installments paid into pending plans, kept apart from the contract balance.
+        installment_escrow: Balance,
+        /// Seats offered by their holder to a specific buyer.
+        seat_sales: StorageHashMap<String, SeatSale>,
+        /// Share in basis points of in-contract resales paid to the organizer.
+        royalty_bps: u16,
+        /// Escrowed offers on sold seats, by seat and bidder.
+        seat_offers: StorageHashMap<(String, AccountId), Balance>,
+        /// Accounts with an offer on each seat.
+        seat_bidders: StorageHashMap<String, Vec<AccountId>>,
+        /// Escrowed offers, kept apart from the contract balance.
+        offer_escrow: Balance,
+        /// Fee charged to the sender of a seat after its purchase, if any.
+        transfer_fee: Option<TransferFee>,
+        /// General admission SKUs by name.
+        skus: StorageHashMap<String, Sku>,
+        /// Names of all SKUs, in the order they were created.
+        sku_names: Vec<String>,
+        /// Number of seats sold.
+        seats_sold: u32,
+        /// Number of seat holds, including expired holds not yet released.
+        seats_held: u32,
+        /// Number of seats whose holder has been checked in.
+        checked_in_count: u32,
+        /// Whether overpayments are accepted and the change returned to the caller.
+        return_change: bool,
+        /// Remaining native value of each voucher, keyed by the hash of its code, see
+        /// `voucher_id`.
+        vouchers: StorageHashMap<Hash, Balance>,
+        /// Registry of banned resellers consulted on purchases, see
+        /// `set_scalper_registry`.
+        scalper_registry: Option<AccountId>,
+        /// Number of tickets burned by verifiers, see `burn`.
+        redeemed_count: Balance,
+        /// Accounts whose tickets are frozen pending a fraud investigation.
+        frozen: StorageHashMap<AccountId, bool>,
+        /// Account that can switch the contract to refund-only mode on its own, see
+        /// `set_emergency_policy`.
+        emergency_guardian: Option<AccountId>,
+        /// Number of verifier signals that switch the contract to refund-only mode,
+        /// `0` if verifiers cannot trigger it.
+        emergency_quorum: u32,
+        /// Verifiers that signalled an emergency.
+        emergency_signals: StorageHashMap<AccountId, bool>,
+        /// Whether the contract is in refund-only mode.
+        refund_mode: bool,
+        /// Number of verifier attestations that the event was held which unlock
+        /// withdrawals before the event end, `0` if withdrawals are not locked.
+        held_threshold: u32,
+        /// Verifiers that attested that the event was held.
+        held_attestations: StorageHashMap<AccountId, bool>,
+        /// Share in basis points of every payment held back in the refund reserve.
+        reserve_bps: u16,
+        /// Timestamp at which the refund window closes and the reserve is released.
+        reserve_until: Timestamp,
+        /// Funds held back for refunds, see `set_refund_reserve`.
+        refund_reserve: Balance,
+        /// Time in milliseconds after a purchase during which verifiers can reverse it.
+        reversal_window: Timestamp,
+        /// Append-only log of privileged actions, by position.
+        admin_log: StorageHashMap<u64, AdminLogEntry>,
+        /// Number of entries in the admin log.
+        admin_log_len: u64,
+        /// Seats that can only be transferred with the consent of both parties, see
+        /// `transfer_seat_signed`.
+        dual_signature_seats: StorageHashMap<String, bool>,
+        /// Delegation of the gate rights of each lent seat, see `delegate_seat`.
+        seat_delegations: StorageHashMap<String, SeatDelegation>,
+        /// Number of sessions (e.g. festival days) a ticket grants entry to. `0` is
+        /// treated as a single session.
+        session_count: u32,
+        /// Sessions each seat has been checked in to.
+        seat_sessions: StorageHashMap<String, Vec<u32>>,
+        /// Number of seats checked in to each session.
+        session_checked_in_count: StorageHashMap<u32, u32>,
+        /// Start timestamps of the dates of a recurring series by date id. All dates
+        /// share the seat definitions of the contract.
+        series_dates: StorageHashMap<u32, Timestamp>,
+        /// Number of series dates, which is also the id of the next date.
+        series_date_count: u32,
+        /// Holders of seats sold for a series date by `(date_id, seat)`.
+        series_seat_owner: StorageHashMap<(u32, String), AccountId>,
+        /// Purchase records of seats sold for a series date, for refunds.
+        series_purchases: StorageHashMap<(u32, String), SeatPurchase>,
+        /// Number of seats sold for each series date.
+        series_sold_count: StorageHashMap<u32, u32>,
+        /// Seats of series dates whose holder has been checked in.
+        series_checked_in: StorageHashMap<(u32, String), bool>,
+        /// Tickets bought for each general admission zone by `(zone, buyer)`.
+        zone_tickets: StorageHashMap<(String, AccountId), u32>,
+        /// Tickets checked in at the gate of each zone by `(zone, buyer)`.
+        zone_admissions: StorageHashMap<(String, AccountId), u32>,
+        /// Number of tickets checked in at the gate of each zone.
+        zone_checked_in_count: StorageHashMap<String, u32>,
+        /// Zone tickets of each buyer that have not been checked in yet, across all
+        /// zones. Their tokens cannot be transferred.
+        unused_zone_ticket_count: StorageHashMap<AccountId, u32>,
+        /// Seats reserved for holders with an accessibility eligibility attestation.
+        accessible_seats: StorageHashMap<String, bool>,
+        /// Accounts whose accessibility eligibility has been attested by a verifier.
+        accessibility_attested: StorageHashMap<AccountId, bool>,
+        /// Concession categories attested by verifiers. Accounts without one are adults.
+        buyer_categories: StorageHashMap<AccountId, BuyerCategory>,
+        /// Prices of concession categories in basis points of the regular price.
+        category_price_bps: StorageHashMap<BuyerCategory, u16>,
+        /// Demand-based pricing policy, if enabled.
+        surge_pricing: Option<SurgePricing>,
+        /// Price multiplier in basis points as of `surge_window`.
+        surge_bps: u16,
+        /// Block window the surge sales are counted in.
+        surge_window: BlockNumber,
+        /// Tickets sold in `surge_window`.
+        surge_sold: u32,
+        /// Minimum price of in-contract resales, if set.
+        resale_floor: Option<ResaleFloor>,
+        /// Treasury receiving withdrawals instead of the owner, if set.
+        treasury: Option<AccountId>,
+        /// Change of the treasury waiting for the withdrawal timelock to pass.
+        pending_treasury: Option<PendingTreasury>,
+        /// Change of the payout stream waiting for the withdrawal timelock to pass.
+        pending_stream: Option<PendingStream>,
+    }
+
+    /// A seat lent by its holder to another account, see `delegate_seat`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct SeatDelegation {
+        /// Holder of the seat that granted the delegation. The delegation ends when
+        /// the seat changes hands.
+        pub holder: AccountId,
+        /// Account that controls the seat at check-in.
+        pub delegate: AccountId,
+        /// Timestamp until which the delegation is active.
+        pub until: Timestamp,
+    }
+
+    /// A privileged action recorded in the admin log, see `admin_log`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct AdminLogEntry {
+        /// Account that performed the action.
+        pub caller: AccountId,
+        /// Timestamp of the action.
+        pub timestamp: Timestamp,
+        /// Name of the message or admin action, e.g. `set_tier_price`.
+        pub action: String,
+        /// SCALE encoded parameters of the action, in the order of the message
+        /// arguments.
+        pub params: Vec<u8>,
+    }
+
+    /// Description of the event the tickets are for, see `event_metadata`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct EventMetadata {
+        /// Name of the event.
+        pub name: String,
+        /// Venue the event takes place at.
+        pub venue: String,
+        /// Timestamp at which the event takes place.
+        pub date: Timestamp,
+        /// URI of an image shown on the ticket.
+        pub image_uri: String,
+    }
+
+    /// Question put to ticket holders, see `open_proposal`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct GovernanceProposal {
+        /// The question voted on.
+        pub question: String,
+        /// Timestamp after which no votes are accepted.
+        pub deadline: Timestamp,
+        /// Snapshot the voting weights are taken from.
+        pub snapshot_id: u32,
+        /// Tickets voting in favour.
+        pub yes: Balance,
+        /// Tickets voting against.
+        pub no: Balance,
+        /// Whether the proposal has been closed.
+        pub closed: bool,
+    }
+
+    /// Per-block release of the proceeds to a payout address, see `claim_stream`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Stream {
+        /// Account the proceeds are paid out to.
+        pub payout: AccountId,
+        /// Amount released every block.
+        pub rate_per_block: Balance,
+        /// Block of the last claim.
+        pub last_claim: BlockNumber,
+    }
+
+    /// Hidden choice of seats to buy, see `commit_purchase`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Commitment {
+        /// Position in the commit order.
+        pub id: u64,
+        /// Hash of the buyer, the seats and a salt.
+        pub hash: Hash,
+        /// Deposit covering the price of the seats.
+        pub deposit: Balance,
+        /// Block the commitment was made in.
+        pub committed_at: BlockNumber,
+        /// Seats revealed for the commitment.
+        pub seats: Vec<String>,
+        /// Whether the seats have been revealed.
+        pub revealed: bool,
+    }
+
+    /// Purchase proposed by a company that awaits a second signer, see
+    /// `propose_purchase`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct PurchaseProposal {
+        /// Company buying and receiving the seats.
+        pub company: AccountId,
+        /// Seats to buy.
+        pub seats: Vec<String>,
+        /// Deposit covering the price of the seats.
+        pub deposit: Balance,
+        /// Timestamp after which the proposal can no longer be confirmed.
+        pub expires: Timestamp,
+    }
+
+    /// Seats reserved by the owner for a buyer who pays later, see `create_invoice`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Invoice {
+        /// Account the seats are reserved for.
+        pub buyer: AccountId,
+        /// Reserved seats.
+        pub seats: Vec<String>,
+        /// Price of each seat, in the order of `seats`.
+        pub prices: Vec<Balance>,
+        /// Total price of the seats, excluding the service fee.
+        pub amount: Balance,
+        /// Timestamp after which the reservation lapses.
+        pub deadline: Timestamp,
+    }
+
+    /// Seat paid for in installments, see `start_installments`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct InstallmentPlan {
+        /// Account paying for the seat.
+        pub buyer: AccountId,
+        /// Price of the seat, including the service fee.
+        pub total: Balance,
+        /// Service fee included in `total`.
+        pub fee: Balance,
+        /// Minimum amount of each installment.
+        pub installment: Balance,
+        /// Amount paid so far.
+        pub paid: Balance,
+        /// Timestamp by which the next installment has to be paid.
+        pub next_due: Timestamp,
+    }
+
+    /// Seat offered by its holder to a specific buyer, see `offer_seat`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct SeatSale {
+        /// Holder selling the seat.
+        pub seller: AccountId,
+        /// Account the seat is offered to.
+        pub buyer: AccountId,
+        /// Price the buyer has to pay.
+        pub price: Balance,
+        /// Timestamp after which the offer can no longer be accepted.
+        pub deadline: Timestamp,
+    }
+
+    /// Temporary reservation of a seat for a buyer.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct SeatHold {
+        /// Account holding the seat.
+        pub holder: AccountId,
+        /// Timestamp after which the hold expires.
+        pub until: Timestamp,
+        /// Deposit paid for the hold.
+        pub deposit: Balance,
+    }
+
+    /// Ascending-bid auction for a single seat.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Auction {
+        /// Lowest acceptable bid.
+        pub reserve: Balance,
+        /// Timestamp after which no bids are accepted.
+        pub ends_at: Timestamp,
+        /// Account with the highest bid so far.
+        pub highest_bidder: Option<AccountId>,
+        /// Highest bid so far.
+        pub highest_bid: Balance,
+    }
+
+    /// Descending-price auction: the seat price decays per block from `start_price`
+    /// down to `floor_price`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct DutchAuction {
+        /// Seat price at `start_block`.
+        pub start_price: Balance,
+        /// Lowest price the seat price decays to.
+        pub floor_price: Balance,
+        /// Amount the seat price decreases by every block.
+        pub decay_per_block: Balance,
+        /// Block at which the auction started.
+        pub start_block: BlockNumber,
+    }
+
+    /// Demand-based pricing: every `step_seats` tickets sold within a window of
+    /// `window` blocks raise prices by `step_bps`, and every window with fewer sales
+    /// lowers them by `step_bps`, within `floor_bps` and `ceiling_bps` of the regular
+    /// price.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct SurgePricing {
+        /// Price change per step, in basis points of the regular price.
+        pub step_bps: u16,
+        /// Tickets that have to be sold within a window to raise prices by a step.
+        pub step_seats: u32,
+        /// Length of a sales window in blocks.
+        pub window: BlockNumber,
+        /// Lowest price multiplier in basis points, at most `10_000`.
+        pub floor_bps: u16,
+        /// Highest price multiplier in basis points, at least `10_000`.
+        pub ceiling_bps: u16,
+    }
+
+    /// A lottery allocating seats at random among the accounts that entered it.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Lottery {
+        /// Seats allocated by the lottery.
+        pub seats: Vec<String>,
+        /// Deposit for an entry, which is the price of a seat.
+        pub deposit: Balance,
+        /// Timestamp after which no entries are accepted.
+        pub entry_end: Timestamp,
+        /// BLAKE2 hash of the secret seed the draw is made with.
+        pub seed_hash: Hash,
+        /// Block of the last entry, or of the start while nobody entered. The draw
+        /// needs on-chain randomness from a later block.
+        pub last_entry_block: BlockNumber,
+        /// Whether the seats have been drawn or the lottery has lapsed undrawn.
+        pub drawn: bool,
+    }
+
+    /// A general admission zone sold by capacity rather than by seat.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Zone {
+        /// Maximum number of tickets sold for the zone.
+        pub capacity: u32,
+        /// Number of tickets sold for the zone.
+        pub sold: u32,
+        /// Price of a single ticket for the zone.
+        pub price: Balance,
+    }
+
+    /// A general admission product, e.g. early bird tickets, with its own price and
+    /// inventory.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Sku {
+        /// Maximum number of tickets sold under the SKU.
+        pub inventory: u32,
+        /// Number of tickets sold under the SKU.
+        pub sold: u32,
+        /// Price of a single ticket of the SKU.
+        pub price: Balance,
+    }
+
+    /// Record of the purchase of a single seat.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct SeatPurchase {
+        /// Account that paid for the seat.
+        pub payer: AccountId,
+        /// Account the seat was bought for.
+        pub recipient: AccountId,
+        /// Native amount paid for the seat.
+        pub price: Balance,
+        /// Timestamp of the purchase.
+        pub purchased_at: Timestamp,
+        /// Concession category the seat was priced at, to be validated at the gate.
+        pub category: BuyerCategory,
+    }
+
+    /// A requested withdrawal of the contract balance.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct PendingWithdrawal {
+        pub amount: Balance,
+        pub executable_at: BlockNumber,
+    }
+
+    /// A requested change of the withdrawal destination, see `request_treasury`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct PendingTreasury {
+        /// New treasury, or `None` to withdraw to the owner again.
+        pub treasury: Option<AccountId>,
+        /// Block from which the change can be executed.
+        pub executable_at: BlockNumber,
+    }
+
+    /// A requested change of the payout stream, see `request_stream`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct PendingStream {
+        /// New payout address and amount released per block, or `None` to stop streaming.
+        pub stream: Option<(AccountId, Balance)>,
+        /// Block from which the change can be executed.
+        pub executable_at: BlockNumber,
+    }
+
+    /// Reason a verifier moved a holder to another seat.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ReassignReason {
+        /// The original seat became unusable, e.g. obstructed view.
+        SeatUnusable,
+        /// The holder needs an accessible seat.
+        Accessibility,
+        /// The holder was upgraded.
+        Upgrade,
+        /// Any other reason.
+        Other,
+    }
+
+    /// Sale state of a seat.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum SeatStatus {
+        /// The seat can be bought.
+        Available,
+        /// The seat is held by a buyer, a lottery or an auction until `until`.
+        Held { until: Timestamp },
+        /// The seat has been sold to `owner`.
+        Sold { owner: AccountId },
+        /// The holder of the seat has been checked in.
+        CheckedIn,
+        /// The seat has been pulled out of sale.
+        Blocked,
+    }
+
+    /// Summary of a deployment, see `contract_info`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ContractInfo {
+        /// Semantic version of the contract code.
+        pub version: String,
+        /// Version of the storage layout.
+        pub storage_version: u32,
+        /// Timestamp at which the contract was deployed.
+        pub deployed_at: Timestamp,
+        /// Contract owner.
+        pub owner: AccountId,
+        /// Base ticket price.
+        pub price: Balance,
+        /// Total token supply.
+        pub total_supply: Balance,
+        /// Whether tickets are sold for specific seats.
+        pub has_seats: bool,
+    }
+
+    /// Sale figures shown by ticket-shop front-ends, see `get_sale_state`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct SaleState {
+        /// Base ticket price.
+        pub price: Balance,
+        /// Price of a seat in each tier.
+        pub tier_prices: Vec<(String, Balance)>,
+        /// Tickets that have left the owner.
+        pub sold: Balance,
+        /// Tickets still held by the owner.
+        pub remaining: Balance,
+        /// Timestamp at which sales stop, if an event start is set.
+        pub sale_end: Option<Timestamp>,
+        /// Whether tickets can currently be purchased, `false` once the event has been
+        /// finalized or has started.
+        pub sales_open: bool,
+        /// Whether no tickets are left for sale.
+        pub sold_out: bool,
+    }
+
+    /// Fee charged on seat transfers after the purchase, see `set_transfer_fee`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub enum TransferFee {
+        /// A fixed amount per transfer.
+        Flat(Balance),
+        /// A share in basis points of the resale price, or of the purchase price for
+        /// gifts, limited to `cap`.
+        Percentage { bps: u16, cap: Balance },
+    }
+
+    /// Minimum price of in-contract resales, see `set_resale_floor`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub enum ResaleFloor {
+        /// A fixed native amount.
+        Absolute(Balance),
+        /// A share in basis points of the seat's face value.
+        FaceValueBps(u16),
+    }
+
+    /// Concession category of a buyer, attested by a verifier and priced with
+    /// `set_category_price`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub enum BuyerCategory {
+        Adult,
+        Child,
+        Student,
+        Senior,
+    }
+
+    /// Reason a ticket was cancelled by the organizer.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum CancelReason {
+        /// The ticket was bought fraudulently.
+        Fraud,
+        /// The ticket was sold by mistake.
+        SaleError,
+        /// Any other reason.
+        Other,
+    }
+
+    /// Reason tickets were burned by a verifier.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum BurnReason {
+        /// The tickets were redeemed for entry.
+        Entry,
+        /// The tickets were confiscated after fraud.
+        Fraud,
+        /// The tickets were refunded.
+        Refund,
+        /// Any other reason.
+        Other,
+    }
+
+    /// A privileged action that requires admin confirmations under multisig control.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub enum AdminAction {
+        /// Withdraw the contract balance to the withdrawal destination.
+        Clear,
+        /// Add a verifier.
+        AddVerifier(AccountId),
+        /// Create or update a seat tier.
+        SetTier {
+            tier: String,
+            price: Balance,
+            seats: Vec<String>,
+        },
+        /// Wind down the event, optionally terminating the contract.
+        FinalizeEvent { terminate: bool },
+        /// Request a timelocked withdrawal of an amount.
+        RequestWithdraw(Balance),
+        /// Request a timelocked change of the treasury.
+        RequestTreasury(Option<AccountId>),
+        /// Request a timelocked change of the payout stream.
+        RequestStream(Option<(AccountId, Balance)>),
+        /// Increase the withdrawal delay to a number of blocks.
+        SetWithdrawDelay(BlockNumber),
+        /// Set the price of a tier.
+        SetTierPrice { tier: String, price: Balance },
+        /// Set the prices of many tiers at once.
+        SetPricesBatch(Vec<(String, Balance)>),
+        /// Set or remove the explicit price of a seat.
+        SetSeatPrice {
+            seat: String,
+            price: Option<Balance>,
+        },
+        /// Create or update a general admission zone.
+        SetZone {
+            zone: String,
+            capacity: u32,
+            price: Balance,
+        },
+        /// Create or update a general admission SKU.
+        SetSku {
+            sku: String,
+            inventory: u32,
+            price: Balance,
+        },
+        /// Set the general admission allocation of a seated contract.
+        SetGeneralAdmission { capacity: u32, price: Balance },
+        /// Start a Dutch auction, replacing any running one.
+        StartDutchAuction {
+            seats: Vec<String>,
+            start_price: Balance,
+            floor_price: Balance,
+            decay_per_block: Balance,
+        },
+        /// End the running Dutch auction.
+        EndDutchAuction,
+        /// Enable or disable demand-based pricing.
+        SetSurgePricing(Option<SurgePricing>),
+        /// Set the price of a buyer category in basis points.
+        SetCategoryPrice {
+            category: BuyerCategory,
+            price_bps: u16,
+        },
+        /// Set the service fee and its collector.
+        SetServiceFee {
+            fee_bps: u16,
+            collector: Option<AccountId>,
+        },
+        /// Set or remove the price oracle and the maximum age of its rate.
+        SetOracle {
+            oracle: Option<AccountId>,
+            max_age: Timestamp,
+        },
+        /// Set whether tips are accepted and their recipient.
+        SetTips {
+            accept: bool,
+            recipient: Option<AccountId>,
+        },
+        /// Set the organizer share of resales in basis points.
+        SetRoyalty(u16),
+        /// Set or remove the seat transfer fee.
+        SetTransferFee(Option<TransferFee>),
+        /// Set or remove the resale floor.
+        SetResaleFloor(Option<ResaleFloor>),
+        /// Add a verifier whose rights lapse at a timestamp.
+        AddVerifierUntil {
+            account: AccountId,
+            expiry: Timestamp,
+        },
+        /// Limit a verifier to a bitmask of scopes.
+        SetVerifierScopes { account: AccountId, scopes: u8 },
+        /// Set the emergency guardian and verifier quorum.
+        SetEmergencyPolicy {
+            guardian: Option<AccountId>,
+            quorum: u32,
+        },
+        /// Replace the admins and confirmation threshold.
+        SetAdmins {
+            admins: Vec<AccountId>,
+            threshold: u32,
+        },
+    }
+
+    /// A proposed privileged action and the admins that confirmed it.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout))]
+    pub struct Proposal {
+        pub action: AdminAction,
+        pub confirmations: Vec<AccountId>,
+        pub executed: bool,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -48,6 +931,16 @@ mod erc20 {
         value: Balance,
     }
 
+    /// Event emitted when the contract is deployed, recording who deployed it for
+    /// which owner.
+    #[ink(event)]
+    pub struct Deployed {
+        #[ink(topic)]
+        deployer: AccountId,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
     /// Event emitted when an approval occurs that `spender` is allowed to withdraw
     /// up to the amount of `value` tokens from `owner`.
     #[ink(event)]
@@ -59,633 +952,10074 @@ mod erc20 {
         value: Balance,
     }
 
-    /// The ERC-20 error types.
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-    pub enum Error {
-        /// Returned if not enough balance to fulfill a request is available.
-        InsufficientBalance,
-        /// Returned if not enough allowance to fulfill a request is available.
-        InsufficientAllowance,
-        /// Incorrect price
-        IncorrectPrice,
-        /// Not owner
-        NotOwner,
-        /// Not Verifier
-        NotVerifier,
-        /// Cannot fetch
-        CannotFetch,
-        /// Seat Taken
-        SeatTaken,
-        /// Mismatch between seats and amount of tickets
-        SeatMismatch
+    /// Event emitted instead of individual `Transfer` events when a batch transfer
+    /// is aggregated.
+    #[ink(event)]
+    pub struct BatchTransfer {
+        #[ink(topic)]
+        from: AccountId,
+        transfers: Vec<(AccountId, Balance)>,
     }
 
-    /// The ERC-20 result type.
-    pub type Result<T> = core::result::Result<T, Error>;
+    /// Event emitted when the owner requests a timelocked withdrawal.
+    #[ink(event)]
+    pub struct WithdrawalRequested {
+        amount: Balance,
+        executable_at: BlockNumber,
+    }
 
-    impl Erc20 {
-        /// Creates a new ERC-20 contract with the specified initial supply.
-        #[ink(constructor)]
-        pub fn new(initial_supply: Balance, price: u128, owner: AccountId,seats:Vec<String>) -> Self {
-            ink_lang::codegen::initialize_contract(|contract| {
-                Self::new_init(contract, initial_supply,price,owner,seats)
-            })
-        }
+    /// Event emitted when the owner requests a timelocked change of the treasury.
+    #[ink(event)]
+    pub struct TreasuryChangeRequested {
+        treasury: Option<AccountId>,
+        executable_at: BlockNumber,
+    }
 
-        /// Default initializes the ERC-20 contract with the specified initial supply.
-        fn new_init(&mut self, initial_supply: Balance, price: u128, owner: AccountId,seats:Vec<String>) {
-            let mut has_seats = true;
-            if seats.clone().len()==0{
-                has_seats = false;
-            }
-            let caller = Self::env().caller();
-                let instance = Self {
-                    total_supply: Lazy::new(initial_supply),
-                    balances: Default::default(),
-                    allowances: Default::default(),
-                    price,
-                    owner,
-                    contract_balance:0,
-                    proof_key: Default::default(),
-                    verifier: Default::default(),
-                    seats,
-                    seat_taken: Default::default(),
-                    seat_balance: Default::default(),
-                    has_seats
+    /// Event emitted when the treasury receiving withdrawals changes.
+    #[ink(event)]
+    pub struct TreasuryChanged {
+        treasury: Option<AccountId>,
+    }
 
+    /// Event emitted when a change of the payout stream is requested.
+    #[ink(event)]
+    pub struct StreamChangeRequested {
+        stream: Option<(AccountId, Balance)>,
+        executable_at: BlockNumber,
+    }
 
+    /// Event emitted when the payout stream changes.
+    #[ink(event)]
+    pub struct StreamChanged {
+        stream: Option<(AccountId, Balance)>,
+    }
 
-                };
+    /// Event emitted when a requested withdrawal is cancelled.
+    #[ink(event)]
+    pub struct WithdrawalCancelled {
+        amount: Balance,
+    }
 
+    /// Event emitted when funds are withdrawn from the contract balance.
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
 
+    /// Event emitted when a verifier signals an emergency.
+    #[ink(event)]
+    pub struct EmergencySignalled {
+        #[ink(topic)]
+        verifier: AccountId,
+        signals: u32,
+    }
 
-            self.balances.insert(&owner, &initial_supply);
-            Lazy::set(&mut self.total_supply, initial_supply);
-            Self::env().emit_event(Transfer {
-                from: None,
-                to: Some(owner),
-                value: initial_supply,
-            });
-        }
+    /// Event emitted when the contract switches to refund-only mode.
+    #[ink(event)]
+    pub struct RefundModeActivated {
+        #[ink(topic)]
+        by: AccountId,
+    }
 
-        /// Returns the total token supply.
-        #[ink(message)]
-        pub fn total_supply(&self) -> Balance {
-            *self.total_supply
-        }
+    /// Event emitted when a payer reclaims their payments in refund-only mode.
+    #[ink(event)]
+    pub struct EmergencyRefunded {
+        #[ink(topic)]
+        payer: AccountId,
+        amount: Balance,
+    }
 
-        #[ink(message)]
-        pub fn contract_balance(&self) -> Balance {
-            return self.contract_balance
-        }
+    /// Event emitted when a verifier attests that the event was held.
+    #[ink(event)]
+    pub struct EventHeldAttested {
+        #[ink(topic)]
+        verifier: AccountId,
+        attestations: u32,
+    }
 
-        #[ink(message)]
-        pub fn proof(&self, to: AccountId) -> Vec<u8> {
-            return self.proof_key.get(&to).unwrap().clone()
-        }
+    /// Event emitted when the storage layout is migrated.
+    #[ink(event)]
+    pub struct StorageMigrated {
+        from: u32,
+        to: u32,
+    }
 
-        #[ink(message)]
-        pub fn get_owner(&self) -> AccountId {
-            return self.owner;
-        }
+    /// Event emitted when the event is wound down.
+    #[ink(event)]
+    pub struct Finalized {
+        swept: Balance,
+        terminated: bool,
+    }
 
+    /// Event emitted when a buyer disputes the purchase of a seat.
+    #[ink(event)]
+    pub struct DisputeRaised {
+        #[ink(topic)]
+        buyer: AccountId,
+        seat: String,
+        amount: Balance,
+    }
 
-        #[ink(message)]
-        pub fn is_verifier(&self,to: AccountId) -> bool {
-            *self.verifier.get(&to).unwrap_or(&false)
-        } 
+    /// Event emitted when a dispute is resolved by a verifier.
+    #[ink(event)]
+    pub struct DisputeResolved {
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        arbiter: AccountId,
+        seat: String,
+        amount: Balance,
+        refunded: bool,
+    }
 
+    /// Event emitted when a verifier grants or revokes the KYC attestation of an account.
+    #[ink(event)]
+    pub struct KycAttestation {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        verifier: AccountId,
+        attested: bool,
+    }
 
-        #[ink(message)]
-        pub fn get_price(&self) -> u128 {
-            return self.price;
-        }
- 
-        
-        /// Returns the account balance for the specified `owner`.
-        ///
-        /// Returns `0` if the account is non-existent.
-        #[ink(message)]
-        pub fn balance_of(&self, owner: AccountId) -> Balance {
-            self.balance_of_impl(&owner)
-        }
+    /// Event emitted when a verifier grants or revokes the accessibility eligibility of
+    /// an account.
+    #[ink(event)]
+    pub struct AccessibilityAttestation {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        verifier: AccountId,
+        eligible: bool,
+    }
 
-        /// Returns the account balance for the specified `owner`.
-        ///
-        /// Returns `0` if the account is non-existent.
-        ///
-        /// # Note
-        ///
-        /// Prefer to call this method over `balance_of` since this
-        /// works using references which are more efficient in Wasm.
-        #[inline]
-        fn balance_of_impl(&self, owner: &AccountId) -> Balance {
-            self.balances.get(owner).unwrap_or_default()
-        }
+    /// Event emitted when a verifier attests the concession category of an account.
+    #[ink(event)]
+    pub struct CategoryAttested {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        verifier: AccountId,
+        category: BuyerCategory,
+    }
 
-        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
-        ///
-        /// Returns `0` if no allowance has been set.
-        #[ink(message)]
-        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
-            self.allowance_impl(&owner, &spender)
-        }
+    /// Event emitted when an account is added to or removed from the blacklist.
+    #[ink(event)]
+    pub struct BlacklistUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+        blacklisted: bool,
+    }
 
-        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
-        ///
-        /// Returns `0` if no allowance has been set.
-        ///
-        /// # Note
-        ///
-        /// Prefer to call this method over `allowance` since this
-        /// works using references which are more efficient in Wasm.
-        #[inline]
-        fn allowance_impl(&self, owner: &AccountId, spender: &AccountId) -> Balance {
-            self.allowances.get((owner, spender)).unwrap_or_default()
-        }
+    /// Event emitted when an account is frozen or unfrozen.
+    #[ink(event)]
+    pub struct FreezeUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+        frozen: bool,
+    }
 
+    /// Event emitted when the owner adds seats after deployment.
+    #[ink(event)]
+    pub struct SeatsAdded {
+        seats: Vec<String>,
+    }
 
-        #[ink(message)]
-        pub fn add_verifier(&mut self, to: AccountId) -> Result<()> {
-            let from = self.env().caller();
-            if from == self.owner {
-                self.verifier.insert(to,true);
-                Ok(())
-            }else{
-                return Err(Error::NotOwner);
+    /// Event emitted when the owner pulls seats out of sale or puts them back.
+    #[ink(event)]
+    pub struct SeatsBlocked {
+        seats: Vec<String>,
+        blocked: bool,
+    }
+
+    /// Event emitted when a holder is moved to another seat.
+    #[ink(event)]
+    pub struct SeatReassigned {
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+        /// Hash of `new_seat`, see `seat_id`.
+        #[ink(topic)]
+        seat_id: Hash,
+        old_seat: String,
+        new_seat: String,
+        reason: ReassignReason,
+    }
+
+    /// Event emitted for every seat sold, so the history of a seat can be filtered by
+    /// its `seat_id`.
+    #[ink(event)]
+    pub struct SeatSold {
+        #[ink(topic)]
+        buyer: AccountId,
+        /// Hash of `seat`, see `seat_id`.
+        #[ink(topic)]
+        seat_id: Hash,
+        seat: String,
+        price: Balance,
+    }
+
+    /// Event emitted when seats are purchased.
+    #[ink(event)]
+    pub struct SeatsPurchased {
+        #[ink(topic)]
+        payer: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        seats: Vec<String>,
+        paid: Balance,
+    }
+
+    /// Event emitted when general admission tickets are purchased.
+    #[ink(event)]
+    pub struct GeneralAdmissionPurchased {
+        #[ink(topic)]
+        payer: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        quantity: Balance,
+        paid: Balance,
+    }
+
+    /// Event emitted when tickets for a general admission zone are purchased.
+    #[ink(event)]
+    pub struct ZonePurchased {
+        #[ink(topic)]
+        buyer: AccountId,
+        zone: String,
+        quantity: u32,
+        paid: Balance,
+    }
+
+    /// Event emitted when a verifier checks in a holder at the gate of a general
+    /// admission zone.
+    #[ink(event)]
+    pub struct ZoneCheckedIn {
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        verifier: AccountId,
+        zone: String,
+        /// Number of tickets checked in for the zone so far.
+        checked_in: u32,
+    }
+
+    /// Event emitted when tickets of a general admission SKU are purchased.
+    #[ink(event)]
+    pub struct SkuPurchased {
+        #[ink(topic)]
+        buyer: AccountId,
+        sku: String,
+        quantity: u32,
+        paid: Balance,
+    }
+
+    /// Event emitted when the last ticket of a general admission SKU is sold.
+    #[ink(event)]
+    pub struct SkuSoldOut {
+        sku: String,
+    }
+
+    /// Event emitted when a voucher is redeemed in a purchase.
+    #[ink(event)]
+    pub struct VoucherRedeemed {
+        /// Hash of the voucher code, see `voucher_id`.
+        #[ink(topic)]
+        voucher: Hash,
+        #[ink(topic)]
+        payer: AccountId,
+        amount: Balance,
+        remaining: Balance,
+    }
+
+    /// Event emitted when an account enters the seat lottery.
+    #[ink(event)]
+    pub struct LotteryEntered {
+        #[ink(topic)]
+        entrant: AccountId,
+        deposit: Balance,
+    }
+
+    /// Event emitted when a lottery winner is assigned a seat.
+    #[ink(event)]
+    pub struct LotteryWon {
+        #[ink(topic)]
+        winner: AccountId,
+        seat: String,
+    }
+
+    /// Event emitted when a lottery entrant that did not win claims their deposit back.
+    #[ink(event)]
+    pub struct LotteryRefunded {
+        #[ink(topic)]
+        entrant: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a bid on a seat auction becomes the highest bid.
+    #[ink(event)]
+    pub struct BidPlaced {
+        #[ink(topic)]
+        bidder: AccountId,
+        seat: String,
+        amount: Balance,
+    }
+
+    /// Event emitted when a seat auction is settled. `winner` is `None` if nobody bid or
+    /// the highest bid was paid back.
+    #[ink(event)]
+    pub struct AuctionSettled {
+        #[ink(topic)]
+        winner: Option<AccountId>,
+        seat: String,
+        price: Balance,
+    }
+
+    /// Event emitted when a buyer tips the tip recipient along with a purchase.
+    #[ink(event)]
+    pub struct DonationReceived {
+        #[ink(topic)]
+        donor: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when complimentary seats are airdropped to a recipient.
+    #[ink(event)]
+    pub struct SeatsAirdropped {
+        #[ink(topic)]
+        recipient: AccountId,
+        seats: Vec<String>,
+    }
+
+    /// Event emitted when the organizer cancels a ticket.
+    #[ink(event)]
+    pub struct TicketCancelled {
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+        seat: String,
+        refund: Balance,
+        reason: CancelReason,
+    }
+
+    /// Event emitted when a verifier burns tickets.
+    #[ink(event)]
+    pub struct Burned {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+        reason: BurnReason,
+    }
+
+    /// Event emitted when a verifier reverses the purchase of seats.
+    #[ink(event)]
+    pub struct PurchaseReversed {
+        #[ink(topic)]
+        by: AccountId,
+        seats: Vec<String>,
+        refund: Balance,
+        reason: CancelReason,
+    }
+
+    /// Event emitted when seats for a date of a recurring series are purchased.
+    #[ink(event)]
+    pub struct SeriesSeatsPurchased {
+        #[ink(topic)]
+        payer: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        #[ink(topic)]
+        date_id: u32,
+        seats: Vec<String>,
+        paid: Balance,
+    }
+
+    /// Event emitted when the organizer cancels a ticket for a series date.
+    #[ink(event)]
+    pub struct SeriesTicketCancelled {
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+        #[ink(topic)]
+        date_id: u32,
+        seat: String,
+        refund: Balance,
+        reason: CancelReason,
+    }
+
+    /// Event emitted when a verifier checks in the holder of a seat for a series date.
+    #[ink(event)]
+    pub struct SeriesSeatCheckedIn {
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        verifier: AccountId,
+        #[ink(topic)]
+        date_id: u32,
+        seat: String,
+    }
+
+    /// Event emitted when demand-based pricing adjusts the price multiplier.
+    #[ink(event)]
+    pub struct PriceAdjusted {
+        previous_bps: u16,
+        current_bps: u16,
+    }
+
+    /// Event emitted when a buyer holds seats.
+    #[ink(event)]
+    pub struct SeatsHeld {
+        #[ink(topic)]
+        holder: AccountId,
+        seats: Vec<String>,
+        until: Timestamp,
+    }
+
+    /// Event emitted when a seat hold is released, by purchase, by the holder or
+    /// because it expired.
+    #[ink(event)]
+    pub struct SeatHoldReleased {
+        #[ink(topic)]
+        holder: AccountId,
+        seat: String,
+        expired: bool,
+    }
+
+    /// Event emitted when a verifier checks in the holder of a seat.
+    #[ink(event)]
+    pub struct SeatCheckedIn {
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        verifier: AccountId,
+        /// Hash of `seat`, see `seat_id`.
+        #[ink(topic)]
+        seat_id: Hash,
+        seat: String,
+        session_id: u32,
+    }
+
+    /// Event emitted when the holder of a seat lends it to another account.
+    #[ink(event)]
+    pub struct SeatDelegated {
+        #[ink(topic)]
+        holder: AccountId,
+        #[ink(topic)]
+        delegate: AccountId,
+        /// Hash of `seat`, see `seat_id`.
+        #[ink(topic)]
+        seat_id: Hash,
+        seat: String,
+        until: Timestamp,
+    }
+
+    /// Event emitted when the prices of tiers are changed.
+    #[ink(event)]
+    pub struct PricesUpdated {
+        tiers: Vec<(String, Balance)>,
+    }
+
+    /// Event emitted when the last ticket is sold.
+    #[ink(event)]
+    pub struct SoldOut {
+        timestamp: Timestamp,
+    }
+
+    /// Event emitted when a purchase commitment is settled. `seats` is empty if no
+    /// seats were bought.
+    #[ink(event)]
+    pub struct CommitmentSettled {
+        #[ink(topic)]
+        buyer: AccountId,
+        seats: Vec<String>,
+        refund: Balance,
+    }
+
+    /// Event emitted when a company proposes a purchase.
+    #[ink(event)]
+    pub struct PurchaseProposed {
+        #[ink(topic)]
+        company: AccountId,
+        #[ink(topic)]
+        id: u64,
+        seats: Vec<String>,
+        deposit: Balance,
+    }
+
+    /// Event emitted when a purchase proposal is confirmed or cancelled. `confirmed_by`
+    /// is `None` if no seats were bought.
+    #[ink(event)]
+    pub struct PurchaseProposalSettled {
+        #[ink(topic)]
+        company: AccountId,
+        #[ink(topic)]
+        id: u64,
+        confirmed_by: Option<AccountId>,
+        refund: Balance,
+    }
+
+    /// Event emitted when the owner reserves seats against an invoice.
+    #[ink(event)]
+    pub struct InvoiceCreated {
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        id: u64,
+        seats: Vec<String>,
+        amount: Balance,
+        deadline: Timestamp,
+    }
+
+    /// Event emitted when an invoice is paid and its seats are sold.
+    #[ink(event)]
+    pub struct InvoiceSettled {
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        id: u64,
+    }
+
+    /// Event emitted when an installment is paid for a seat.
+    #[ink(event)]
+    pub struct InstallmentPaid {
+        #[ink(topic)]
+        buyer: AccountId,
+        seat: String,
+        amount: Balance,
+        remaining: Balance,
+    }
+
+    /// Event emitted when an installment plan is forfeited after a missed deadline.
+    #[ink(event)]
+    pub struct InstallmentsForfeited {
+        #[ink(topic)]
+        buyer: AccountId,
+        seat: String,
+        forfeited: Balance,
+        refund: Balance,
+    }
+
+    /// Event emitted when the fee collector withdraws the service fees.
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        collector: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the owner takes a balance snapshot.
+    #[ink(event)]
+    pub struct Snapshot {
+        id: u32,
+    }
+
+    /// Event emitted when a ticket holder votes on a governance proposal.
+    #[ink(event)]
+    pub struct VoteCast {
+        #[ink(topic)]
+        voter: AccountId,
+        #[ink(topic)]
+        id: u64,
+        support: bool,
+        weight: Balance,
+    }
+
+    /// Event emitted when a governance proposal is closed with its final tally.
+    #[ink(event)]
+    pub struct ProposalClosed {
+        #[ink(topic)]
+        id: u64,
+        yes: Balance,
+        no: Balance,
+        passed: bool,
+    }
+
+    /// Event emitted when a holder offers a seat to a specific buyer.
+    #[ink(event)]
+    pub struct SeatOffered {
+        #[ink(topic)]
+        seller: AccountId,
+        #[ink(topic)]
+        buyer: AccountId,
+        seat: String,
+        price: Balance,
+        deadline: Timestamp,
+    }
+
+    /// Event emitted when a seat is sold from one holder to another. `royalty` of
+    /// the price goes to the organizer.
+    #[ink(event)]
+    pub struct SeatResold {
+        #[ink(topic)]
+        seller: AccountId,
+        #[ink(topic)]
+        buyer: AccountId,
+        /// Hash of `seat`, see `seat_id`.
+        #[ink(topic)]
+        seat_id: Hash,
+        seat: String,
+        price: Balance,
+        royalty: Balance,
+    }
+
+    /// Event emitted when an offer is placed on a sold seat, or topped up.
+    #[ink(event)]
+    pub struct OfferPlaced {
+        #[ink(topic)]
+        bidder: AccountId,
+        seat: String,
+        amount: Balance,
+    }
+
+    /// Event emitted when a bidder withdraws its offer on a seat.
+    #[ink(event)]
+    pub struct OfferWithdrawn {
+        #[ink(topic)]
+        bidder: AccountId,
+        seat: String,
+        amount: Balance,
+    }
+
+    /// Event emitted when a seat is gifted from one account to another.
+    #[ink(event)]
+    pub struct SeatGifted {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        /// Hash of `seat`, see `seat_id`.
+        #[ink(topic)]
+        seat_id: Hash,
+        seat: String,
+        /// Optional memo for the recipient, encrypted off-chain.
+        memo: Option<Vec<u8>>,
+    }
+
+    /// The ERC-20 error types.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if not enough allowance to fulfill a request is available.
+        InsufficientAllowance,
+        /// Returned if the transferred value does not match the price of a purchase.
+        IncorrectPrice {
+            expected: Balance,
+            provided: Balance,
+        },
+        /// Caller is not the contract owner
+        NotOwner,
+        /// Caller is not a verifier
+        NotVerifier,
+        /// Seats that have already been sold
+        SeatsUnavailable(Vec<String>),
+        /// Number of seats does not match the amount of tickets
+        SeatMismatch {
+            seats: u32,
+            tickets: Balance,
+        },
+        /// Seat is not part of this event
+        UnknownSeat(String),
+        /// Seat is declared more than once
+        DuplicateSeat(String),
+        /// Seat label is empty
+        EmptySeatLabel,
+        /// Contract was deployed without seats
+        NoSeats,
+        /// Seat is not blocked
+        SeatNotBlocked(String),
+        /// Zone does not exist
+        UnknownZone(String),
+        /// Zone does not have enough capacity left
+        ZoneSoldOut {
+            zone: String,
+            remaining: u32,
+        },
+        /// Zone capacity cannot be lower than the tickets already sold
+        InvalidCapacity,
+        /// General admission allocation does not have enough tickets left
+        GeneralAdmissionSoldOut {
+            remaining: u32,
+        },
+        /// A lottery is still running
+        LotteryActive,
+        /// No lottery is accepting entries
+        LotteryClosed,
+        /// Lottery entry window has not ended yet
+        LotteryOpen,
+        /// Lottery has not been drawn yet
+        LotteryNotDrawn,
+        /// Seed does not match the seed hash of the lottery
+        InvalidLotterySeed,
+        /// Lottery was not drawn within `LOTTERY_REVEAL_WINDOW` and has lapsed
+        LotteryLapsed,
+        /// On-chain randomness from after the last lottery entry is not available yet
+        LotteryEntropyPending,
+        /// Account has already entered the lottery or not claimed its refund
+        AlreadyEntered,
+        /// Account has no lottery deposit to claim
+        NoDeposit,
+        /// Auction parameters are invalid
+        InvalidAuction,
+        /// Seat is not being auctioned
+        UnknownAuction,
+        /// Auction has ended and no longer accepts bids
+        AuctionEnded,
+        /// Auction has not ended yet
+        AuctionNotEnded,
+        /// Bid does not exceed the reserve or the highest bid
+        BidTooLow {
+            minimum: Balance,
+        },
+        /// Tips can not be accepted without a recipient
+        NoTipRecipient,
+        /// Vesting schedule is invalid or less strict than the current one
+        InvalidVesting,
+        /// Hold bounty exceeds the hold deposit
+        InvalidHoldPolicy,
+        /// Seat holds are disabled
+        HoldsDisabled,
+        /// Caller does not hold the seat
+        NoHold,
+        /// Seat has not been sold
+        SeatNotSold(String),
+        /// Seat holder has already been checked in
+        AlreadyCheckedIn,
+        /// Tier does not exist
+        UnknownTier(String),
+        /// Tier name is reserved for seats without a tier
+        ReservedTier,
+        /// Owner has not enough tickets left to fulfill the purchase
+        SoldOut,
+        /// Account has bought the maximum number of tickets for the current block window
+        RateLimited,
+        /// Throttle window must be at least one block
+        InvalidThrottle,
+        /// Commit–reveal purchases are disabled
+        CommitRevealDisabled,
+        /// Account already has an unsettled purchase commitment
+        AlreadyCommitted,
+        /// Account has no purchase commitment
+        NoCommitment,
+        /// Commitment can not be revealed in the block it was made in
+        RevealTooEarly,
+        /// Reveal window of the commitment has closed
+        RevealWindowClosed,
+        /// Reveal window of the commitment is still open
+        RevealWindowOpen,
+        /// Seats and salt do not match the commitment
+        InvalidReveal,
+        /// Voucher code does not exist or has been used up
+        UnknownPromo(String),
+        /// Service fee exceeds 100% or has no collector
+        InvalidFee,
+        /// Caller is not the fee collector
+        NotFeeCollector,
+        /// Proceeds are streamed, lump-sum withdrawals are disabled
+        StreamActive,
+        /// No payout stream is set
+        NoStream,
+        /// Stream rate would release the releasable balance too quickly
+        InvalidStreamRate,
+        /// No stream change has been requested
+        NoPendingStreamChange,
+        /// Stream change request already exists
+        StreamChangePending,
+        /// Buyer has no on-chain identity judgement
+        IdentityRequired,
+        /// Snapshot does not exist
+        UnknownSnapshot,
+        /// Deadline must be in the future
+        InvalidDeadline,
+        /// Governance proposal no longer accepts votes
+        VotingClosed,
+        /// Governance proposal still accepts votes
+        VotingOpen,
+        /// Account has already voted on the governance proposal
+        AlreadyVoted,
+        /// Verifier scopes are empty or contain unknown bits
+        InvalidScopes,
+        /// Tickets have already been sold
+        SalesOpen,
+        /// The event has started
+        EventStarted,
+        /// The check-in grace period after the event start has passed
+        CheckInClosed,
+        /// Purchase proposals are disabled
+        ProposalsDisabled,
+        /// Company has no authorized signers
+        NoSigners,
+        /// Caller is not an authorized signer of the company
+        NotSigner,
+        /// Purchase proposal does not exist
+        UnknownPurchaseProposal,
+        /// Purchase proposal can no longer be confirmed
+        ProposalExpired,
+        /// Invoice does not exist
+        UnknownInvoice,
+        /// Caller is not the buyer of the invoice
+        NotInvoiceBuyer,
+        /// Invoice payment deadline has passed
+        InvoiceExpired,
+        /// Installment policy needs at least two installments, an interval and a forfeit
+        /// of at most 100%
+        InvalidInstallments,
+        /// Installment plans are disabled
+        InstallmentsDisabled,
+        /// Seat has no installment plan
+        NoInstallmentPlan,
+        /// Installment deadline has passed
+        InstallmentOverdue,
+        /// Installment deadline has not passed yet
+        InstallmentNotOverdue,
+        /// Seat is not offered for sale
+        NoSeatSale,
+        /// Caller is not the buyer the seat is offered to
+        NotSaleBuyer,
+        /// Seat sale offer has expired
+        SaleExpired,
+        /// Royalty exceeds 100%
+        InvalidRoyalty,
+        /// Bidder has no offer on the seat
+        NoOffer,
+        /// Holders cannot place offers on their own seat
+        OfferOnOwnSeat,
+        /// Transfer fee exceeds `MAX_TRANSFER_FEE_BPS`
+        InvalidTransferFee,
+        /// Contract sells tickets for specific seats
+        SeatedContract,
+        /// SKU does not exist
+        UnknownSku(String),
+        /// SKU does not have enough inventory left
+        SkuSoldOut {
+            sku: String,
+            remaining: u32,
+        },
+        /// Seat label exceeds `MAX_SEAT_LABEL_LEN`
+        SeatLabelTooLong,
+        /// Scalper registry could not be queried
+        RegistryUnavailable,
+        /// Account is flagged by the scalper registry
+        FlaggedReseller,
+        /// Account is frozen pending an investigation
+        AccountFrozen,
+        /// Caller is neither the emergency guardian nor a signalling verifier
+        NotGuardian,
+        /// Verifier already signalled an emergency
+        AlreadySignalled,
+        /// Contract is in refund-only mode
+        RefundMode,
+        /// Contract is not in refund-only mode
+        NotRefundMode,
+        /// Caller has no payments to reclaim
+        NothingToRefund,
+        /// Withdrawals are locked until the event has ended or was attested as held
+        WithdrawalLocked,
+        /// Verifier already attested that the event was held
+        AlreadyAttested,
+        /// Refund reserve exceeds 100% or would be loosened
+        InvalidReserve,
+        /// Reversal window of the purchase has passed
+        ReversalWindowClosed,
+        /// Seat can only be transferred with signatures of both parties
+        DualSignatureRequired,
+        /// Session is not part of the event
+        UnknownSession(u32),
+        /// Series date does not exist
+        UnknownDate(u32),
+        /// Zone has reached its capacity at the gate
+        ZoneFull(String),
+        /// Holder has no unused tickets for the zone
+        NoZoneTicket(String),
+        /// Accessible seat requires an accessibility eligibility attestation
+        AccessibilityRequired(String),
+        /// Category price exceeds the regular price
+        InvalidCategoryPrice,
+        /// Surge pricing steps are empty or its bounds exclude the regular price
+        InvalidSurgePricing,
+        /// Resale floor exceeds the face value
+        InvalidResaleFloor,
+        /// Resale price is below the resale floor
+        BelowResaleFloor { minimum: Balance },
+        /// Native funds could not be transferred out of the contract
+        TransferFailed,
+        /// Caller does not hold the seat
+        NotSeatOwner,
+        /// Memo exceeds `MAX_MEMO_LEN`
+        MemoTooLong,
+        /// Tickets are soulbound and cannot be transferred
+        NonTransferable,
+        /// Caller is not an approved marketplace
+        MarketplaceNotAllowed,
+        /// Signature deadline has passed
+        SignatureExpired,
+        /// Signature does not match the signer
+        InvalidSignature,
+        /// Receiving contract did not accept the tokens
+        TransferRejected,
+        /// Action must be proposed and confirmed by the admins
+        MultisigRequired,
+        /// Caller is not an admin
+        NotAdmin,
+        /// Admin threshold is larger than the number of admins, or an attestation
+        /// threshold would be lowered
+        InvalidThreshold,
+        /// Proposal does not exist
+        UnknownProposal,
+        /// Proposal has already been executed
+        ProposalExecuted,
+        /// Admin has already confirmed the proposal
+        AlreadyConfirmed,
+        /// Withdrawals must be requested and wait for the timelock
+        WithdrawalTimelocked,
+        /// No withdrawal has been requested
+        NoPendingWithdrawal,
+        /// Withdrawal request already exists
+        WithdrawalPending,
+        /// No treasury change has been requested
+        NoPendingTreasuryChange,
+        /// Treasury change request already exists
+        TreasuryChangePending,
+        /// Treasury changes require a withdrawal delay
+        TimelockRequired,
+        /// Timelock of the withdrawal has not passed yet
+        TimelockNotExpired,
+        /// Withdrawal delay can only be increased
+        InvalidDelay,
+        /// Storage is already at the current version
+        AlreadyMigrated,
+        /// There is no migration from this storage version
+        UnknownStorageVersion(u32),
+        /// Event has been finalized
+        EventFinalized,
+        /// Event has not ended yet
+        EventNotEnded,
+        /// Event end can only be postponed
+        InvalidEventEnd,
+        /// Contract was reentered while moving funds
+        ReentrantCall,
+        /// Price oracle could not be queried
+        OracleUnavailable,
+        /// Price oracle rate is older than the maximum age
+        StaleOraclePrice,
+        /// Caller did not pay for the seat
+        NotBuyer,
+        /// Dispute window of the purchase has passed
+        DisputeWindowClosed,
+        /// Seat already has an open dispute
+        DisputeOpen,
+        /// Seat has no open dispute
+        NoDispute,
+        /// Contract cannot be terminated while disputes are open
+        OpenDisputes,
+        /// Contract cannot be terminated while it holds funds owed to others
+        FundsOutstanding,
+        /// Purchaser has no KYC attestation
+        KycRequired,
+        /// Account is blacklisted
+        Blacklisted,
+    }
+
+    /// The ERC-20 result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Ticketing interface of the contract: purchases, seat queries, check-in and
+    /// refunds, callable by aggregators and venue-management contracts on any
+    /// compliant deployment.
+    #[ink::trait_definition]
+    pub trait TicketSale {
+        /// Purchases `value` tickets for `to` and stores `signature` as proof of ownership.
+        ///
+        /// If the contract has seats, `seats` must hold exactly `value` available seats.
+        /// The transferred value must match the price of the tickets being bought. If tips
+        /// are accepted, anything above the price is forwarded to the tip recipient.
+        ///
+        /// A `voucher` code covers as much of the price as its remaining value allows,
+        /// only the rest has to be transferred. The service fee is not covered.
+        ///
+        /// Accessible seats can only be bought for a `to` with an accessibility
+        /// eligibility attestation, see `set_accessible_seats`. Tickets are charged at
+        /// the price of the concession category attested for `to`, which is recorded in
+        /// the seat's purchase record.
+        #[ink(message, payable)]
+        fn purchase_tickets(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            signature: Vec<u8>,
+            seats: Vec<String>,
+            voucher: Option<String>,
+        ) -> Result<()>;
+
+        /// Returns the exact native amount to transfer with `purchase_tickets` for `seats`
+        /// bought for `to`, accounting for tiers, per-seat prices, a running Dutch
+        /// auction, the oracle rate, the concession category of `to` and the service fee.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoSeats` for contracts without seats, `UnknownSeat` if a seat is not
+        /// part of this event and `UnknownPromo` if the voucher code `promo` does not
+        /// exist or has been used up.
+        #[ink(message)]
+        fn quote(&self, to: AccountId, seats: Vec<String>, promo: Option<String>) -> Result<Balance>;
+
+        /// Returns `true` if all `seats` are available for purchase.
+        #[ink(message)]
+        fn is_seat_available(&self, seats: Vec<String>) -> bool;
+
+        /// Returns `true` if `seat` is part of this event, has not been sold and is not
+        /// blocked or held.
+        #[ink(message)]
+        fn is_seat_free(&self, seat: String) -> bool;
+
+        /// Returns the sale state of `seat`, or `None` if it is not part of this event.
+        #[ink(message)]
+        fn seat_status(&self, seat: String) -> Option<SeatStatus>;
+
+        /// Returns the current holder of `seat`, if it has been sold.
+        #[ink(message)]
+        fn seat_owner(&self, seat: String) -> Option<AccountId>;
+
+        /// Checks in the holder of `seat` at the venue, or its active delegate while the
+        /// seat is lent out with `delegate_seat`.
+        ///
+        /// A ticket grants entry to each of the `session_count` sessions once, so
+        /// `session_id` must be below it.
+        ///
+        /// Only a verifier with the `CAN_CHECK_IN` scope can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownSession` if `session_id` is not part of the event,
+        /// `SeatNotSold` if nobody holds `seat`, `AlreadyCheckedIn` if its holder has been
+        /// checked in to the session before and `CheckInClosed` once the grace period
+        /// after the event start has passed.
+        #[ink(message)]
+        fn check_in(&mut self, seat: String, session_id: u32) -> Result<()>;
+
+        /// Cancels the ticket for `seat` held by `holder`, e.g. after fraud or a sale
+        /// made in error. The recorded purchase price is refunded to the payer from the
+        /// contract balance and the seat becomes available again.
+        ///
+        /// Only the contract owner or a verifier with the `CAN_REFUND` scope can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSeatOwner` if `holder` does not hold `seat`, `DisputeOpen` if the
+        /// seat is disputed and `InsufficientBalance` if the refund is not covered by the
+        /// withdrawable balance.
+        #[ink(message)]
+        fn cancel_ticket(&mut self, holder: AccountId, seat: String, reason: CancelReason) -> Result<()>;
+    }
+
+    /// Version of the storage layout written by this code.
+    pub const STORAGE_VERSION: u32 = 2;
+
+    /// Semantic version of this contract code.
+    pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+    /// Tier that revenue of seats without an explicit tier, and of general admission
+    /// tickets, is accounted to.
+    pub const DEFAULT_TIER: &str = "standard";
+
+    /// Domain separator of payloads signed for `permit`.
+    pub const PERMIT_DOMAIN: &[u8] = b"erc20-seats:permit";
+
+    /// Domain separator of payloads signed for `purchase_with_signature`.
+    pub const PURCHASE_DOMAIN: &[u8] = b"erc20-seats:purchase";
+
+    /// Domain separator of payloads signed to prove ticket ownership, see
+    /// `ticket_payload`.
+    pub const TICKET_DOMAIN: &[u8] = b"erc20-seats:ticket";
+
+    /// Domain separator of payloads signed for `transfer_seat_signed`.
+    pub const SEAT_TRANSFER_DOMAIN: &[u8] = b"erc20-seats:seat-transfer";
+
+    /// Domain separator of ticket hashes encoded in QR codes, see `ticket_hash`.
+    pub const TICKET_HASH_DOMAIN: &[u8] = b"erc20-seats:ticket-hash";
+
+    /// Selector of the `on_received(operator, from, value, data) -> bool` hook that
+    /// contracts receiving tokens through `transfer_with_data` must implement.
+    pub const ON_RECEIVED_SELECTOR: [u8; 4] = ink_lang::selector_bytes!("on_received");
+
+    /// Selector of the `latest_rate() -> (Balance, Timestamp)` message of the price oracle,
+    /// returning the native amount per fiat minor unit and when it was last updated.
+    pub const ORACLE_RATE_SELECTOR: [u8; 4] = ink_lang::selector_bytes!("latest_rate");
+
+    /// Selector of the `is_flagged(account) -> bool` message of the scalper registry,
+    /// returning whether `account` is a banned reseller.
+    pub const IS_FLAGGED_SELECTOR: [u8; 4] = ink_lang::selector_bytes!("is_flagged");
+
+    /// Maximum length in bytes of the memo attached to a gifted seat.
+    pub const MAX_MEMO_LEN: usize = 256;
+
+    /// Maximum length in bytes of a seat label.
+    pub const MAX_SEAT_LABEL_LEN: usize = 32;
+
+    /// Maximum percentage transfer fee in basis points.
+    pub const MAX_TRANSFER_FEE_BPS: u16 = 2_000;
+
+    /// Time in milliseconds after the end of the lottery entry window in which the
+    /// owner has to reveal the seed, after which the lottery lapses.
+    pub const LOTTERY_REVEAL_WINDOW: Timestamp = 86_400_000;
+
+    /// Minimum number of blocks a payout stream takes to release the releasable
+    /// balance, about a day at six second blocks.
+    pub const MIN_STREAM_BLOCKS: BlockNumber = 14_400;
+
+    /// Storage key of the reentrancy guard, far from the keys of the storage struct.
+    const REENTRANCY_GUARD_KEY: [u8; 32] = [0xEE; 32];
+
+    /// Verifier scope allowing to burn tickets.
+    pub const CAN_BURN: u8 = 1;
+
+    /// Verifier scope allowing to check in seat holders.
+    pub const CAN_CHECK_IN: u8 = 1 << 1;
+
+    /// Verifier scope allowing to cancel tickets and resolve disputes with refunds.
+    pub const CAN_REFUND: u8 = 1 << 2;
+
+    /// Verifier scope allowing to attest the KYC and accessibility eligibility of
+    /// accounts.
+    pub const CAN_ATTEST_KYC: u8 = 1 << 3;
+
+    /// All verifier scopes, granted by `add_verifier`.
+    pub const ALL_SCOPES: u8 = CAN_BURN | CAN_CHECK_IN | CAN_REFUND | CAN_ATTEST_KYC;
+
+    impl Erc20 {
+        /// Creates a new ERC-20 contract with the specified initial supply.
+        ///
+        /// # Panics
+        ///
+        /// If `seats` contains duplicate, empty or too long labels, or if seats are given
+        /// but their number does not match `initial_supply`. Deployment fails in that
+        /// case.
+        #[ink(constructor)]
+        pub fn new(initial_supply: Balance, price: u128, owner: AccountId,seats:Vec<String>) -> Self {
+            ink_lang::codegen::initialize_contract(|contract| {
+                Self::new_init(contract, initial_supply,price,owner,seats)
+            })
+        }
+
+        /// Default initializes the ERC-20 contract with the specified initial supply.
+        fn new_init(&mut self, initial_supply: Balance, price: u128, owner: AccountId,seats:Vec<String>) {
+            if let Err(error) = Self::validate_seats(&seats, initial_supply) {
+                panic!("invalid seats: {:?}", error)
+            }
+            self.has_seats = !seats.is_empty();
+            for seat in seats.iter() {
+                self.seat_taken.insert(seat.clone(), false);
+            }
+            self.seats = seats;
+            self.price = price;
+            self.owner = owner;
+            self.storage_version = STORAGE_VERSION;
+            self.deployed_at = Self::env().block_timestamp();
+            self.set_balance(&owner, initial_supply);
+            Lazy::set(&mut self.total_supply, initial_supply);
+            Self::env().emit_event(Transfer {
+                from: None,
+                to: Some(owner),
+                value: initial_supply,
+            });
+            Self::env().emit_event(Deployed {
+                deployer: Self::env().caller(),
+                owner,
+            });
+        }
+
+        /// Checks that `seats` holds unique, non-empty labels, one for each ticket of
+        /// `initial_supply`. An empty `seats` deploys a contract without seats.
+        fn validate_seats(seats: &[String], initial_supply: Balance) -> Result<()> {
+            if seats.is_empty() {
+                return Ok(())
+            }
+            if seats.len() as u128 != initial_supply {
+                return Err(Error::SeatMismatch {
+                    seats: seats.len() as u32,
+                    tickets: initial_supply,
+                })
+            }
+            let mut labels = BTreeSet::new();
+            for seat in seats {
+                if seat.is_empty() {
+                    return Err(Error::EmptySeatLabel)
+                }
+                if seat.len() > MAX_SEAT_LABEL_LEN {
+                    return Err(Error::SeatLabelTooLong)
+                }
+                if !labels.insert(seat) {
+                    return Err(Error::DuplicateSeat(seat.clone()))
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns the total token supply.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            *self.total_supply
+        }
+
+        /// Returns the number of tickets that have been redeemed or otherwise burned.
+        #[ink(message)]
+        pub fn redeemed_count(&self) -> Balance {
+            self.redeemed_count
+        }
+
+        /// Returns the number of tickets that have not been redeemed yet, including
+        /// unsold tickets of the owner.
+        #[ink(message)]
+        pub fn outstanding_supply(&self) -> Balance {
+            *self.total_supply - self.redeemed_count
+        }
+
+        #[ink(message)]
+        pub fn contract_balance(&self) -> Balance {
+            return self.contract_balance
+        }
+
+        /// Returns the ownership proof stored for `to`, or `None` if it never purchased.
+        #[ink(message)]
+        pub fn proof(&self, to: AccountId) -> Option<Vec<u8>> {
+            self.proof_key.get(&to).cloned()
+        }
+
+        /// Returns up to `limit` stored proofs with their accounts, skipping the first
+        /// `offset`, in the order they were first stored.
+        #[ink(message)]
+        pub fn proofs(&self, offset: u32, limit: u32) -> Vec<(AccountId, Vec<u8>)> {
+            self.proof_key
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .map(|(account, proof)| (*account, proof.clone()))
+                .collect()
+        }
+
+        /// Returns the total amount of native funds `account` has paid into the contract.
+        ///
+        /// Returns `0` if the account never purchased a ticket.
+        #[ink(message)]
+        pub fn total_paid(&self, account: AccountId) -> Balance {
+            *self.total_paid.get(&account).unwrap_or(&0)
+        }
+
+        /// Returns the contract owner.
+        ///
+        /// The selector is pinned to the one derived from `get_owner`, so clients keep
+        /// working if the message is renamed.
+        #[ink(message, selector = 0x07FCD0B1)]
+        pub fn get_owner(&self) -> AccountId {
+            return self.owner;
+        }
+
+
+        #[ink(message)]
+        pub fn is_verifier(&self,to: AccountId) -> bool {
+            let expired = self
+                .verifier_expiry
+                .get(&to)
+                .map_or(false, |expiry| self.env().block_timestamp() > *expiry);
+            *self.verifier.get(&to).unwrap_or(&false) && !expired
+        } 
+
+        /// Returns `true` if `account` is an active verifier holding all bits of `scope`.
+        #[ink(message)]
+        pub fn has_scope(&self, account: AccountId, scope: u8) -> bool {
+            self.is_verifier(account) && self.verifier_scopes_of(account) & scope == scope
+        }
+
+        /// Returns the bitmask of scopes granted to `account`, `0` if it is no verifier.
+        #[ink(message)]
+        pub fn verifier_scopes_of(&self, account: AccountId) -> u8 {
+            if !*self.verifier.get(&account).unwrap_or(&false) {
+                return 0
+            }
+            *self.verifier_scopes.get(&account).unwrap_or(&ALL_SCOPES)
+        }
+
+
+        /// Returns the base ticket price.
+        ///
+        /// The selector is pinned to the one derived from `get_price`, so clients keep
+        /// working if the message is renamed.
+        #[ink(message, selector = 0xF23D4B6C)]
+        pub fn get_price(&self) -> u128 {
+            return self.price;
+        }
+
+        /// Returns up to `limit` taken seats, skipping the first `offset` taken seats.
+        ///
+        /// Seats are returned in the order they were declared in the constructor.
+        #[ink(message)]
+        pub fn taken_seats(&self, offset: u32, limit: u32) -> Vec<String> {
+            self.seats
+                .iter()
+                .filter(|seat| *self.seat_taken.get(*seat).unwrap_or(&false))
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect()
+        }
+
+        /// Returns the number of seats that have been sold.
+        #[ink(message)]
+        pub fn sold_seat_count(&self) -> u32 {
+            self.seats_sold
+        }
+
+        /// Returns the number of seat holds, including expired holds that have not been
+        /// released yet.
+        #[ink(message)]
+        pub fn held_seat_count(&self) -> u32 {
+            self.seats_held
+        }
+
+        /// Returns the number of seats whose holder has been checked in to at least one
+        /// session.
+        #[ink(message)]
+        pub fn checked_in_count(&self) -> u32 {
+            self.checked_in_count
+        }
+
+        /// Returns the number of seats whose holder has been checked in to `session_id`.
+        #[ink(message)]
+        pub fn session_checked_in_count(&self, session_id: u32) -> u32 {
+            *self.session_checked_in_count.get(&session_id).unwrap_or(&0)
+        }
+
+        /// Returns `true` if the holder of `seat` has been checked in to `session_id`.
+        #[ink(message)]
+        pub fn is_checked_in(&self, seat: String, session_id: u32) -> bool {
+            self.checked_in_sessions(seat).contains(&session_id)
+        }
+
+        /// Returns the sessions the holder of `seat` has been checked in to, in check-in
+        /// order.
+        #[ink(message)]
+        pub fn checked_in_sessions(&self, seat: String) -> Vec<u32> {
+            match self.seat_sessions.get(&seat) {
+                Some(sessions) => sessions.clone(),
+                // check-ins from before sessions were tracked are to the first session
+                None if self.checked_in.contains_key(&seat) => core::iter::once(0).collect(),
+                None => Vec::new(),
+            }
+        }
+
+        /// Returns `true` if no tickets are left for sale: every seat is sold or blocked,
+        /// and the general admission allocation and all zones are sold out. Contracts
+        /// without seats are sold out once the owner has no tickets left.
+        #[ink(message)]
+        pub fn is_sold_out(&self) -> bool {
+            if !self.has_seats {
+                return self.remaining_tickets() == 0
+            }
+            let seats_left = self.seats.iter().any(|seat| {
+                !*self.seat_taken.get(seat).unwrap_or(&true) && !self.is_seat_blocked(seat.clone())
+            });
+            let zones_left = self
+                .zone_names
+                .iter()
+                .filter_map(|zone| self.zones.get(zone))
+                .any(|zone| zone.sold < zone.capacity);
+            !seats_left && !zones_left && self.ga_sold >= self.ga_capacity
+        }
+
+        /// Returns the number of tickets that can still be sold.
+        ///
+        /// This is the part of the owner's balance that has not been sold yet, which is
+        /// the only measure of inventory for contracts deployed without seats.
+        #[ink(message)]
+        pub fn remaining_tickets(&self) -> Balance {
+            self.balance_of_impl(&self.owner)
+        }
+
+        /// Returns the prices, sale counts and sale window in a single call.
+        #[ink(message)]
+        pub fn get_sale_state(&self) -> SaleState {
+            let remaining = self.remaining_tickets();
+            SaleState {
+                price: self.price,
+                tier_prices: self
+                    .tiers
+                    .iter()
+                    .map(|tier| (tier.clone(), *self.tier_price.get(tier).unwrap_or(&self.price)))
+                    .collect(),
+                sold: self.total_supply().saturating_sub(remaining),
+                remaining,
+                sale_end: self.event_start,
+                sales_open: !self.finalized && !self.has_event_started(),
+                sold_out: self.is_sold_out(),
+            }
+        }
+
+        /// Returns the gross revenue collected per tier.
+        ///
+        /// The first entry is `DEFAULT_TIER`, which holds sales of seats without a tier
+        /// and of general admission tickets.
+        #[ink(message)]
+        pub fn revenue_by_tier(&self) -> Vec<(String, Balance)> {
+            let mut revenue = Vec::new();
+            revenue.push((String::from(DEFAULT_TIER), self.tier_revenue_of(DEFAULT_TIER)));
+            for tier in self.tiers.iter() {
+                revenue.push((tier.clone(), self.tier_revenue_of(tier)));
+            }
+            revenue
+        }
+
+        /// Returns the number of seats declared for this event.
+        ///
+        /// Returns `0` if the contract was deployed without seats.
+        #[ink(message)]
+        pub fn total_seat_count(&self) -> u32 {
+            self.seats.len() as u32
+        }
+ 
+        
+        /// Returns up to `limit` accounts holding tickets, skipping the first `offset`.
+        ///
+        /// The order is not stable: when an account no longer holds tickets, the last
+        /// holder takes its place.
+        #[ink(message)]
+        pub fn holders(&self, offset: u32, limit: u32) -> Vec<AccountId> {
+            self.holders
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect()
+        }
+
+        /// Returns the number of accounts holding tickets.
+        #[ink(message)]
+        pub fn holder_count(&self) -> u32 {
+            self.holders.len() as u32
+        }
+
+        /// Sets the token balance of `account` to `value`, keeping the holder registry up
+        /// to date.
+        fn set_balance(&mut self, account: &AccountId, value: Balance) {
+            self.record_snapshot(account);
+            self.balances.insert(account, &value);
+            match (value > 0, self.holder_index.get(account).copied()) {
+                (true, None) => {
+                    self.holder_index.insert(*account, self.holders.len() as u32);
+                    self.holders.push(*account);
+                }
+                (false, Some(index)) => {
+                    self.holder_index.take(account);
+                    self.holders.swap_remove(index as usize);
+                    if let Some(moved) = self.holders.get(index as usize).cloned() {
+                        self.holder_index.insert(moved, index);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        /// Takes a snapshot of all balances and returns its id. Balances are recorded
+        /// lazily, the first time they change after the snapshot.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn snapshot(&mut self) -> Result<u32> {
+            self.ensure_owner()?;
+            self.snapshot_id += 1;
+            self.env().emit_event(Snapshot {
+                id: self.snapshot_id,
+            });
+            Ok(self.snapshot_id)
+        }
+
+        /// Returns the id of the latest snapshot, `0` if none was taken.
+        #[ink(message)]
+        pub fn current_snapshot_id(&self) -> u32 {
+            self.snapshot_id
+        }
+
+        /// Returns the balance `account` had when snapshot `snapshot_id` was taken.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownSnapshot` if no snapshot with that id was taken.
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, snapshot_id: u32) -> Result<Balance> {
+            if snapshot_id == 0 || snapshot_id > self.snapshot_id {
+                return Err(Error::UnknownSnapshot)
+            }
+            let recorded = self.balance_snapshots.get(&account).and_then(|history| {
+                history
+                    .iter()
+                    .find(|(id, _)| *id >= snapshot_id)
+                    .map(|(_, balance)| *balance)
+            });
+            Ok(recorded.unwrap_or_else(|| self.balance_of_impl(&account)))
+        }
+
+        /// Records the balance of `account` for the latest snapshot, if it has not been
+        /// recorded since the snapshot was taken.
+        fn record_snapshot(&mut self, account: &AccountId) {
+            if self.snapshot_id == 0 {
+                return
+            }
+            let mut history = self.balance_snapshots.get(account).cloned().unwrap_or_default();
+            if history.last().map_or(false, |(id, _)| *id == self.snapshot_id) {
+                return
+            }
+            history.push((self.snapshot_id, self.balance_of_impl(account)));
+            self.balance_snapshots.insert(*account, history);
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balance_of_impl(&owner)
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        ///
+        /// # Note
+        ///
+        /// Prefer to call this method over `balance_of` since this
+        /// works using references which are more efficient in Wasm.
+        #[inline]
+        fn balance_of_impl(&self, owner: &AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowance_impl(&owner, &spender)
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        ///
+        /// # Note
+        ///
+        /// Prefer to call this method over `allowance` since this
+        /// works using references which are more efficient in Wasm.
+        #[inline]
+        fn allowance_impl(&self, owner: &AccountId, spender: &AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or_default()
+        }
+
+
+        #[ink(message)]
+        pub fn add_verifier(&mut self, to: AccountId) -> Result<()> {
+            let from = self.env().caller();
+            if from == self.owner {
+                self.ensure_no_multisig()?;
+                self.verifier.insert(to,true);
+                self.verifier_expiry.take(&to);
+                self.verifier_scopes.take(&to);
+                self.log_admin_action("add_verifier", to);
+                Ok(())
+            }else{
+                return Err(Error::NotOwner);
+
+            }
+        }
+
+        /// Adds `to` as a verifier whose rights lapse after `expiry`, e.g. for temporary
+        /// gate staff.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn add_verifier_until(&mut self, to: AccountId, expiry: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.add_verifier_until_impl(to, expiry)
+        }
+
+        /// Adds `to` as a verifier until `expiry`.
+        fn add_verifier_until_impl(&mut self, to: AccountId, expiry: Timestamp) -> Result<()> {
+            if expiry <= self.env().block_timestamp() {
+                return Err(Error::InvalidDeadline)
+            }
+            self.verifier.insert(to, true);
+            self.verifier_expiry.insert(to, expiry);
+            self.log_admin_action("add_verifier_until", (to, expiry));
+            Ok(())
+        }
+
+        /// Limits the verifier `to` to the bitmask `scopes` of `CAN_BURN`, `CAN_CHECK_IN`,
+        /// `CAN_REFUND` and `CAN_ATTEST_KYC`, making it a verifier if it is not one yet.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidScopes` if `scopes` is empty or contains unknown bits.
+        #[ink(message)]
+        pub fn set_verifier_scopes(&mut self, to: AccountId, scopes: u8) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_verifier_scopes_impl(to, scopes)
+        }
+
+        /// Limits the verifier `to` to `scopes`.
+        fn set_verifier_scopes_impl(&mut self, to: AccountId, scopes: u8) -> Result<()> {
+            if scopes == 0 || scopes & !ALL_SCOPES != 0 {
+                return Err(Error::InvalidScopes)
+            }
+            self.verifier.insert(to, true);
+            self.verifier_scopes.insert(to, scopes);
+            self.log_admin_action("set_verifier_scopes", (to, scopes));
+            Ok(())
+        }
+
+        /// Returns the timestamp after which the verifier rights of `account` lapse, if
+        /// they are time-limited.
+        #[ink(message)]
+        pub fn verifier_expiry(&self, account: AccountId) -> Option<Timestamp> {
+            self.verifier_expiry.get(&account).copied()
+        }
+
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            self.ensure_transferable(&from)?;
+            self.transfer_from_to(&from, &to, value)
+        }
+
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`,
+        /// passing `data` along to the recipient.
+        ///
+        /// If `to` is a contract, its `on_received` hook is called first and the transfer
+        /// only happens if it accepts the tokens.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransferRejected` if the receiving contract does not accept the
+        /// tokens, and `InsufficientBalance` if there are not enough tokens on the
+        /// caller's account balance.
+        #[ink(message)]
+        pub fn transfer_with_data(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<()> {
+            let from = self.env().caller();
+            self.ensure_transferable(&from)?;
+            if self.balance_of_impl(&from) < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.non_reentrant(|contract| {
+                if contract.env().is_contract(&to)
+                    && !contract.notify_received(&from, &from, &to, value, data)
+                {
+                    return Err(Error::TransferRejected)
+                }
+                contract.transfer_from_to(&from, &to, value)
+            })
+        }
+
+        /// Transfers tokens from the caller's account to each account in `transfers`.
+        ///
+        /// Either all transfers succeed or none of them is applied. If `aggregate` is
+        /// `true` a single `BatchTransfer` event is emitted, otherwise a `Transfer`
+        /// event is emitted per transfer.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance to cover all transfers.
+        #[ink(message)]
+        pub fn transfer_batch(&mut self, transfers: Vec<(AccountId, Balance)>, aggregate: bool) -> Result<()> {
+            let from = self.env().caller();
+            self.ensure_transferable(&from)?;
+            let total = transfers
+                .iter()
+                .try_fold(0u128, |total, (_, value)| total.checked_add(*value))
+                .ok_or(Error::InsufficientBalance)?;
+            if self.balance_of_impl(&from) < total {
+                return Err(Error::InsufficientBalance)
+            }
+            if transfers.iter().any(|(to, _)| self.is_blacklisted(*to)) {
+                return Err(Error::Blacklisted)
+            }
+            for (to, value) in transfers.iter() {
+                if aggregate {
+                    self.move_balance(&from, to, *value)?;
+                } else {
+                    self.transfer_from_to(&from, to, *value)?;
+                }
+            }
+            if aggregate {
+                self.env().emit_event(BatchTransfer { from, transfers });
+            }
+            Ok(())
+        }
+
+        /// Purchases `value` tickets for `buyer` on their behalf, authorized by an
+        /// off-chain ECDSA `signature` of `buyer`.
+        ///
+        /// This lets a relayer submit the purchase and pay the fees and ticket price for
+        /// a buyer without native balance, while the purchase is attributed to `buyer`.
+        /// The signed message is the Blake2x256 hash of the SCALE encoded tuple
+        /// `(PURCHASE_DOMAIN, contract, buyer, value, proof, seats, nonce, deadline)`,
+        /// where `nonce` is the current `nonce_of(buyer)`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SignatureExpired` if `deadline` has passed and `InvalidSignature` if
+        /// the signature was not made by `buyer`, besides the errors of `purchase_tickets`.
+        #[ink(message, payable)]
+        pub fn purchase_with_signature(
+            &mut self,
+            buyer: AccountId,
+            value: Balance,
+            proof: Vec<u8>,
+            seats: Vec<String>,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::SignatureExpired)
+            }
+            let payload = (
+                PURCHASE_DOMAIN,
+                self.env().account_id(),
+                buyer,
+                value,
+                &proof,
+                &seats,
+                self.nonce_of(buyer),
+                deadline,
+            );
+            self.use_signature(&buyer, &payload, &signature)?;
+            self.non_reentrant(|contract| contract.purchase_impl(buyer, buyer, value, proof, seats, None))
+        }
+
+        /// Returns `true` if `seat` can be bought by `buyer`, i.e. it is free apart from
+        /// a hold of `buyer` itself.
+        fn is_seat_free_for(&self, buyer: Option<AccountId>, seat: &String) -> bool {
+            self.is_seat_open_for(buyer, seat) && !self.seat_claims.contains_key(seat)
+        }
+
+        /// Returns `true` if `seat` can be bought by `buyer`, disregarding claims of
+        /// revealed purchase commitments.
+        fn is_seat_open_for(&self, buyer: Option<AccountId>, seat: &String) -> bool {
+            let held = self
+                .active_hold(seat)
+                .map_or(false, |hold| Some(hold.holder) != buyer);
+            !*self.seat_taken.get(seat).unwrap_or(&true)
+                && !held
+                && !self.lottery_seats.contains_key(seat)
+                && !self.auctions.contains_key(seat)
+                && self.active_invoice(seat).is_none()
+                && self.active_installment_plan(seat).is_none()
+                && !self.is_seat_blocked(seat.clone())
+        }
+
+        /// Sets the number of blocks purchase commitments can be revealed in after the
+        /// block they were made in, and the deposit a commitment needs at least. A
+        /// `window` of `0` disables commit–reveal purchases.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_reveal_window(&mut self, window: BlockNumber, min_deposit: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.reveal_window = window;
+            self.commit_min_deposit = min_deposit;
+            Ok(())
+        }
+
+        /// Returns the number of blocks a purchase commitment can be revealed in.
+        #[ink(message)]
+        pub fn reveal_window(&self) -> BlockNumber {
+            self.reveal_window
+        }
+
+        /// Returns the deposit a purchase commitment needs at least.
+        #[ink(message)]
+        pub fn commit_min_deposit(&self) -> Balance {
+            self.commit_min_deposit
+        }
+
+        /// Returns the commitment `buyer` has to submit to `commit_purchase` for buying
+        /// `seats`, hidden by `salt`.
+        ///
+        /// Clients should compute this off-chain, as the BLAKE2 hash of the SCALE
+        /// encoded `(buyer, seats, salt)`, to keep their choice private.
+        #[ink(message)]
+        pub fn commitment_hash(&self, buyer: AccountId, seats: Vec<String>, salt: [u8; 32]) -> Hash {
+            Hash::from(self.env().hash_encoded::<Blake2x256, _>(&(buyer, seats, salt)))
+        }
+
+        /// Returns the purchase commitment of `buyer`, if any.
+        #[ink(message)]
+        pub fn commitment(&self, buyer: AccountId) -> Option<Commitment> {
+            self.commitments.get(&buyer).cloned()
+        }
+
+        /// Commits to buying seats without revealing them, so the choice cannot be
+        /// frontrun. The transferred value is a deposit that has to cover the price of
+        /// the seats and the service fee; anything left over is paid back on
+        /// settlement.
+        ///
+        /// `hash` is computed as described in `commitment_hash`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `IncorrectPrice` if the deposit is below the minimum deposit.
+        #[ink(message, payable)]
+        pub fn commit_purchase(&mut self, hash: Hash) -> Result<()> {
+            let buyer = self.env().caller();
+            self.ensure_can_purchase(&buyer)?;
+            if self.reveal_window == 0 {
+                return Err(Error::CommitRevealDisabled)
+            }
+            if self.commitments.contains_key(&buyer) {
+                return Err(Error::AlreadyCommitted)
+            }
+            let deposit = self.env().transferred_balance();
+            if deposit < self.commit_min_deposit {
+                return Err(Error::IncorrectPrice {
+                    expected: self.commit_min_deposit,
+                    provided: deposit,
+                })
+            }
+            let id = self.next_commitment_id;
+            self.next_commitment_id += 1;
+            self.commitments.insert(
+                buyer,
+                Commitment {
+                    id,
+                    hash,
+                    deposit,
+                    committed_at: self.env().block_number(),
+                    seats: Vec::new(),
+                    revealed: false,
+                },
+            );
+            self.commit_escrow += deposit;
+            Ok(())
+        }
+
+        /// Reveals the `seats` and `salt` of the caller's commitment in a block after
+        /// it was made, claiming the seats.
+        ///
+        /// Commitments are honored in commit order: a seat claimed by a later
+        /// commitment is taken over, a seat claimed by an earlier one is unavailable.
+        /// The claimed seats are bought on `settle_commitment`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `RevealTooEarly` in the block of the commitment,
+        /// `RevealWindowClosed` after the reveal window, `InvalidReveal` if `seats`
+        /// and `salt` do not match the commitment and `IncorrectPrice` if the deposit
+        /// does not cover the price of the seats and the service fee.
+        #[ink(message)]
+        pub fn reveal_purchase(&mut self, seats: Vec<String>, salt: [u8; 32]) -> Result<()> {
+            let buyer = self.env().caller();
+            let mut commitment = self.commitments.get(&buyer).cloned().ok_or(Error::NoCommitment)?;
+            let now = self.env().block_number();
+            if now <= commitment.committed_at {
+                return Err(Error::RevealTooEarly)
+            }
+            if commitment.revealed || now > commitment.committed_at + self.reveal_window {
+                return Err(Error::RevealWindowClosed)
+            }
+            if self.commitment_hash(buyer, seats.clone(), salt) != commitment.hash {
+                return Err(Error::InvalidReveal)
+            }
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| {
+                    let claimed_earlier = self
+                        .seat_claims
+                        .get(*seat)
+                        .map_or(false, |(id, _)| *id < commitment.id);
+                    claimed_earlier || !self.is_seat_open_for(Some(buyer), seat)
+                })
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            let rate = self.native_rate()?;
+            let price = self.required_payment(seats.len() as u128, &seats, rate);
+            let fee = self.service_fee_of(price);
+            if price + fee > commitment.deposit {
+                return Err(Error::IncorrectPrice {
+                    expected: price + fee,
+                    provided: commitment.deposit,
+                })
+            }
+            for seat in seats.iter() {
+                self.seat_claims.insert(seat.clone(), (commitment.id, buyer));
+            }
+            commitment.seats = seats;
+            commitment.revealed = true;
+            self.commitments.insert(buyer, commitment);
+            Ok(())
+        }
+
+        /// Settles the commitment of `buyer` once its reveal window has closed: the
+        /// seats it still claims are bought with its deposit, including the service
+        /// fee, and the rest of the deposit is paid back. If the deposit no longer
+        /// covers the seats, or the commitment was never revealed, the whole deposit is
+        /// paid back.
+        ///
+        /// Anyone can call this.
+        #[ink(message)]
+        pub fn settle_commitment(&mut self, buyer: AccountId) -> Result<()> {
+            let commitment = self.commitments.get(&buyer).cloned().ok_or(Error::NoCommitment)?;
+            if self.env().block_number() <= commitment.committed_at + self.reveal_window {
+                return Err(Error::RevealWindowOpen)
+            }
+            let won: Vec<String> = commitment
+                .seats
+                .iter()
+                .filter(|seat| self.seat_claims.get(*seat) == Some(&(commitment.id, buyer)))
+                .cloned()
+                .collect();
+            let rate = self.native_rate()?;
+            let price = self.required_payment(won.len() as u128, &won, rate);
+            let fee = self.service_fee_of(price);
+            let owner = self.owner;
+            let buy = !won.is_empty()
+                && price + fee <= commitment.deposit
+                && self.balance_of_impl(&owner) >= won.len() as u128
+                && self.ensure_can_purchase(&buyer).is_ok();
+            self.non_reentrant(|contract| {
+                for seat in won.iter() {
+                    contract.seat_claims.take(seat);
+                }
+                contract.commitments.take(&buyer);
+                contract.commit_escrow -= commitment.deposit;
+                let mut refund = commitment.deposit;
+                if buy {
+                    contract.transfer_from_to(&owner, &buyer, won.len() as u128)?;
+                    for seat in won.iter() {
+                        let price = contract.seat_price(seat) * rate;
+                        contract.sell_seat(seat, buyer, price);
+                    }
+                    contract.collect_fee(fee);
+                    refund -= price + fee;
+                }
+                if refund > 0 {
+                    contract.env().transfer(buyer, refund).map_err(|_| Error::TransferFailed)?;
+                }
+                contract.env().emit_event(CommitmentSettled {
+                    buyer,
+                    seats: if buy { won } else { Vec::new() },
+                    refund,
+                });
+                Ok(())
+            })
+        }
+
+        /// Sets the time in milliseconds a purchase proposal can be confirmed in. A
+        /// `window` of `0` disables purchase proposals.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_approval_window(&mut self, window: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.approval_window = window;
+            Ok(())
+        }
+
+        /// Returns the time a purchase proposal can be confirmed in.
+        #[ink(message)]
+        pub fn approval_window(&self) -> Timestamp {
+            self.approval_window
+        }
+
+        /// Authorizes `signers` to confirm the purchase proposals of the caller,
+        /// replacing any previous signers.
+        #[ink(message)]
+        pub fn set_purchase_signers(&mut self, signers: Vec<AccountId>) -> Result<()> {
+            let company = self.env().caller();
+            if signers.is_empty() {
+                self.purchase_signers.take(&company);
+            } else {
+                self.purchase_signers.insert(company, signers);
+            }
+            Ok(())
+        }
+
+        /// Returns the accounts authorized to confirm the purchase proposals of
+        /// `company`.
+        #[ink(message)]
+        pub fn purchase_signers(&self, company: AccountId) -> Vec<AccountId> {
+            self.purchase_signers.get(&company).cloned().unwrap_or_default()
+        }
+
+        /// Returns the purchase proposal `id`, if it is pending.
+        #[ink(message)]
+        pub fn purchase_proposal(&self, id: u64) -> Option<PurchaseProposal> {
+            self.purchase_proposals.get(&id).cloned()
+        }
+
+        /// Proposes buying `seats` for the caller, to be confirmed by one of its
+        /// authorized signers within the approval window. The transferred value is a
+        /// deposit that has to cover the price of the seats and the service fee;
+        /// anything left over is paid back when the proposal is settled.
+        ///
+        /// The seats are not reserved and are only allocated on confirmation.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ProposalsDisabled` without an approval window, `NoSeats` if the
+        /// contract has no seats and `NoSigners` if the caller has not authorized any
+        /// signers.
+        #[ink(message, payable)]
+        pub fn propose_purchase(&mut self, seats: Vec<String>) -> Result<u64> {
+            let company = self.env().caller();
+            self.ensure_can_purchase(&company)?;
+            if self.approval_window == 0 {
+                return Err(Error::ProposalsDisabled)
+            }
+            if !self.has_seats || seats.is_empty() {
+                return Err(Error::NoSeats)
+            }
+            if !self.purchase_signers.contains_key(&company) {
+                return Err(Error::NoSigners)
+            }
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let deposit = self.env().transferred_balance();
+            let id = self.next_purchase_proposal_id;
+            self.next_purchase_proposal_id += 1;
+            self.purchase_proposals.insert(
+                id,
+                PurchaseProposal {
+                    company,
+                    seats: seats.clone(),
+                    deposit,
+                    expires: self.env().block_timestamp().saturating_add(self.approval_window),
+                },
+            );
+            self.proposal_escrow += deposit;
+            self.env().emit_event(PurchaseProposed {
+                company,
+                id,
+                seats,
+                deposit,
+            });
+            Ok(id)
+        }
+
+        /// Confirms the purchase proposal `id`, allocating its seats to the company and
+        /// taking the price and service fee from its deposit. The rest of the deposit is
+        /// paid back to the company.
+        ///
+        /// Only an authorized signer of the company, other than the company itself, can
+        /// call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ProposalExpired` after the approval window, `SeatsUnavailable` if a
+        /// seat has been sold in the meantime and `IncorrectPrice` if the deposit does
+        /// not cover the price.
+        #[ink(message)]
+        pub fn confirm_purchase(&mut self, id: u64) -> Result<()> {
+            let signer = self.env().caller();
+            let proposal = self.purchase_proposals.get(&id).cloned().ok_or(Error::UnknownPurchaseProposal)?;
+            let company = proposal.company;
+            if signer == company || !self.purchase_signers(company).contains(&signer) {
+                return Err(Error::NotSigner)
+            }
+            if self.env().block_timestamp() > proposal.expires {
+                return Err(Error::ProposalExpired)
+            }
+            self.ensure_can_purchase(&company)?;
+            let unavailable: Vec<String> = proposal
+                .seats
+                .iter()
+                .filter(|seat| !self.is_seat_free_for(Some(company), seat))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            let quantity = proposal.seats.len() as u128;
+            let rate = self.native_rate()?;
+            let price = self.required_payment(quantity, &proposal.seats, rate);
+            let fee = self.service_fee_of(price);
+            if price + fee > proposal.deposit {
+                return Err(Error::IncorrectPrice {
+                    expected: price + fee,
+                    provided: proposal.deposit,
+                })
+            }
+            let owner = self.owner;
+            if self.balance_of_impl(&owner) < quantity {
+                return Err(Error::SoldOut)
+            }
+            self.throttle(&company, &company, quantity)?;
+            self.non_reentrant(|contract| {
+                contract.purchase_proposals.take(&id);
+                contract.proposal_escrow -= proposal.deposit;
+                contract.transfer_from_to(&owner, &company, quantity)?;
+                let mut payouts = Vec::new();
+                for seat in proposal.seats.iter() {
+                    let price = contract.seat_price(seat) * rate;
+                    payouts.extend(contract.take_hold(seat, None));
+                    contract.sell_seat(seat, company, price);
+                }
+                contract.collect_fee(fee);
+                let refund = proposal.deposit - price - fee;
+                payouts.push((company, refund));
+                contract.env().emit_event(PurchaseProposalSettled {
+                    company,
+                    id,
+                    confirmed_by: Some(signer),
+                    refund,
+                });
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Cancels the purchase proposal `id` and pays its deposit back to the company.
+        ///
+        /// The company can cancel at any time, anyone else once the approval window has
+        /// passed.
+        #[ink(message)]
+        pub fn cancel_purchase_proposal(&mut self, id: u64) -> Result<()> {
+            let proposal = self.purchase_proposals.get(&id).cloned().ok_or(Error::UnknownPurchaseProposal)?;
+            let company = proposal.company;
+            if self.env().caller() != company && self.env().block_timestamp() <= proposal.expires {
+                return Err(Error::NotSigner)
+            }
+            self.non_reentrant(|contract| {
+                contract.purchase_proposals.take(&id);
+                contract.proposal_escrow -= proposal.deposit;
+                if proposal.deposit > 0 {
+                    contract.env().transfer(company, proposal.deposit).map_err(|_| Error::TransferFailed)?;
+                }
+                contract.env().emit_event(PurchaseProposalSettled {
+                    company,
+                    id,
+                    confirmed_by: None,
+                    refund: proposal.deposit,
+                });
+                Ok(())
+            })
+        }
+
+        /// Returns the invoice `id`, if it exists. Its deadline may have passed.
+        #[ink(message)]
+        pub fn invoice(&self, id: u64) -> Option<Invoice> {
+            self.invoices.get(&id).cloned()
+        }
+
+        /// Reserves `seats` for `buyer` until `deadline`, at their current price. The
+        /// buyer pays with `settle_invoice`; unpaid seats go back on sale at the
+        /// deadline.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoSeats` if the contract has no seats, `InvalidDeadline` if
+        /// `deadline` has passed and `SeatsUnavailable` if a seat cannot be reserved.
+        #[ink(message)]
+        pub fn create_invoice(&mut self, buyer: AccountId, seats: Vec<String>, deadline: Timestamp) -> Result<u64> {
+            self.ensure_owner()?;
+            if !self.has_seats || seats.is_empty() {
+                return Err(Error::NoSeats)
+            }
+            if deadline <= self.env().block_timestamp() {
+                return Err(Error::InvalidDeadline)
+            }
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| !self.is_seat_free((*seat).clone()))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            let rate = self.native_rate()?;
+            let prices: Vec<Balance> = seats.iter().map(|seat| self.seat_price(seat) * rate).collect();
+            let amount = prices.iter().sum();
+            let id = self.next_invoice_id;
+            self.next_invoice_id += 1;
+            for seat in seats.iter() {
+                self.invoiced_seats.insert(seat.clone(), id);
+            }
+            self.invoices.insert(
+                id,
+                Invoice {
+                    buyer,
+                    seats: seats.clone(),
+                    prices,
+                    amount,
+                    deadline,
+                },
+            );
+            self.env().emit_event(InvoiceCreated {
+                buyer,
+                id,
+                seats,
+                amount,
+                deadline,
+            });
+            Ok(id)
+        }
+
+        /// Pays the invoice `invoice_id`, buying its reserved seats. The transferred
+        /// value has to match the invoiced amount plus the service fee.
+        ///
+        /// Only the buyer of the invoice can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvoiceExpired` after the deadline and `IncorrectPrice` if the
+        /// transferred value does not match.
+        #[ink(message, payable)]
+        pub fn settle_invoice(&mut self, invoice_id: u64) -> Result<()> {
+            let buyer = self.env().caller();
+            let invoice = self.invoices.get(&invoice_id).cloned().ok_or(Error::UnknownInvoice)?;
+            if buyer != invoice.buyer {
+                return Err(Error::NotInvoiceBuyer)
+            }
+            if self.env().block_timestamp() > invoice.deadline {
+                return Err(Error::InvoiceExpired)
+            }
+            self.ensure_can_purchase(&buyer)?;
+            let fee = self.service_fee_of(invoice.amount);
+            let excess = self.check_payment(invoice.amount + fee)?;
+            let unavailable: Vec<String> = invoice
+                .seats
+                .iter()
+                .filter(|seat| {
+                    self.invoiced_seats.get(*seat) != Some(&invoice_id)
+                        || *self.seat_taken.get(*seat).unwrap_or(&true)
+                })
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            let quantity = invoice.seats.len() as u128;
+            let owner = self.owner;
+            if self.balance_of_impl(&owner) < quantity {
+                return Err(Error::SoldOut)
+            }
+            self.non_reentrant(|contract| {
+                contract.transfer_from_to(&owner, &buyer, quantity)?;
+                for (seat, price) in invoice.seats.iter().zip(invoice.prices.iter()) {
+                    contract.invoiced_seats.take(seat);
+                    contract.sell_seat(seat, buyer, *price);
+                }
+                contract.collect_fee(fee);
+                contract.invoices.take(&invoice_id);
+                contract.env().emit_event(InvoiceSettled { buyer, id: invoice_id });
+                contract.settle_excess(buyer, excess)
+            })
+        }
+
+        /// Cancels the invoice `invoice_id`, putting its reserved seats back on sale.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn cancel_invoice(&mut self, invoice_id: u64) -> Result<()> {
+            self.ensure_owner()?;
+            let invoice = self.invoices.take(&invoice_id).ok_or(Error::UnknownInvoice)?;
+            for seat in invoice.seats.iter() {
+                if self.invoiced_seats.get(seat) == Some(&invoice_id) {
+                    self.invoiced_seats.take(seat);
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns the invoice reserving `seat` if its deadline has not passed yet.
+        fn active_invoice(&self, seat: &String) -> Option<&Invoice> {
+            self.invoiced_seats
+                .get(seat)
+                .and_then(|id| self.invoices.get(id))
+                .filter(|invoice| self.env().block_timestamp() <= invoice.deadline)
+        }
+
+        /// Sets the installment policy: seats are paid in `count` installments due every
+        /// `interval` milliseconds, and `forfeit_bps` of the paid installments is
+        /// forfeited when a deadline is missed. A `count` of `0` disables new plans.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_installment_policy(&mut self, count: u32, interval: Timestamp, forfeit_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            if count != 0 && (count < 2 || interval == 0 || forfeit_bps > 10_000) {
+                return Err(Error::InvalidInstallments)
+            }
+            self.installment_count = count;
+            self.installment_interval = interval;
+            self.installment_forfeit_bps = forfeit_bps;
+            Ok(())
+        }
+
+        /// Returns the number of installments, the interval between their deadlines and
+        /// the forfeited share in basis points.
+        #[ink(message)]
+        pub fn installment_policy(&self) -> (u32, Timestamp, u16) {
+            (self.installment_count, self.installment_interval, self.installment_forfeit_bps)
+        }
+
+        /// Returns the installment plan of `seat`, if any. Its deadline may have passed.
+        #[ink(message)]
+        pub fn installment_plan(&self, seat: String) -> Option<InstallmentPlan> {
+            self.installment_plans.get(&seat).cloned()
+        }
+
+        /// Starts paying for `seat` in installments, the transferred value being the first
+        /// one. The seat is held for the caller while the installments are paid on time.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InstallmentsDisabled` without an installment policy and
+        /// `SeatsUnavailable` if the seat cannot be bought by the caller.
+        #[ink(message, payable)]
+        pub fn start_installments(&mut self, seat: String) -> Result<()> {
+            let buyer = self.env().caller();
+            self.ensure_can_purchase(&buyer)?;
+            if self.installment_count == 0 {
+                return Err(Error::InstallmentsDisabled)
+            }
+            if !self.seat_taken.contains_key(&seat) {
+                return Err(Error::UnknownSeat(seat))
+            }
+            if !self.is_seat_free_for(Some(buyer), &seat) {
+                return Err(Error::SeatsUnavailable(Vec::from([seat])))
+            }
+            if self.balance_of_impl(&self.owner) == 0 {
+                return Err(Error::SoldOut)
+            }
+            let price = self.seat_price(&seat) * self.native_rate()?;
+            let fee = self.service_fee_of(price);
+            let total = price + fee;
+            let count = self.installment_count as u128;
+            let plan = InstallmentPlan {
+                buyer,
+                total,
+                fee,
+                installment: (total + count - 1) / count,
+                paid: 0,
+                next_due: self.env().block_timestamp(),
+            };
+            let amount = self.check_installment(&plan)?;
+            self.non_reentrant(|contract| {
+                // an overdue plan left on the seat is forfeited first
+                let mut payouts = contract.forfeit_plan(&seat);
+                payouts.extend(contract.take_hold(&seat, None));
+                contract.pay_installment_impl(seat, plan, amount)?;
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Pays the next installment for `seat`. Once the seat is paid in full, it is
+        /// sold to the caller.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InstallmentOverdue` after the deadline of the installment and
+        /// `IncorrectPrice` if less than an installment, or more than the remaining
+        /// amount, is transferred.
+        #[ink(message, payable)]
+        pub fn pay_installment(&mut self, seat: String) -> Result<()> {
+            let buyer = self.env().caller();
+            let plan = self
+                .installment_plans
+                .get(&seat)
+                .filter(|plan| plan.buyer == buyer)
+                .cloned()
+                .ok_or(Error::NoInstallmentPlan)?;
+            if self.env().block_timestamp() > plan.next_due {
+                return Err(Error::InstallmentOverdue)
+            }
+            self.ensure_can_purchase(&buyer)?;
+            let amount = self.check_installment(&plan)?;
+            self.non_reentrant(|contract| contract.pay_installment_impl(seat, plan, amount))
+        }
+
+        /// Forfeits the overdue installment plan of `seat`: the forfeited share of the
+        /// paid installments goes to the contract balance, the rest is paid back to the
+        /// buyer and the seat goes back on sale.
+        ///
+        /// Anyone can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InstallmentNotOverdue` if the plan is not overdue yet and
+        /// `TransferFailed` if the buyer cannot be paid back.
+        #[ink(message)]
+        pub fn forfeit_installments(&mut self, seat: String) -> Result<()> {
+            let plan = self.installment_plans.get(&seat).ok_or(Error::NoInstallmentPlan)?;
+            if self.env().block_timestamp() <= plan.next_due {
+                return Err(Error::InstallmentNotOverdue)
+            }
+            self.non_reentrant(|contract| {
+                let payouts = contract.forfeit_plan(&seat);
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Returns the transferred value if it is a valid installment of `plan`: at
+        /// least one installment and at most the remaining amount.
+        fn check_installment(&self, plan: &InstallmentPlan) -> Result<Balance> {
+            let amount = self.env().transferred_balance();
+            let remaining = plan.total - plan.paid;
+            let minimum = plan.installment.min(remaining);
+            if amount == 0 || amount > remaining || amount < minimum {
+                return Err(Error::IncorrectPrice {
+                    expected: minimum,
+                    provided: amount,
+                })
+            }
+            Ok(amount)
+        }
+
+        /// Adds the installment `amount` to `plan` for `seat`, selling the seat to the
+        /// buyer once it is paid in full.
+        fn pay_installment_impl(&mut self, seat: String, mut plan: InstallmentPlan, amount: Balance) -> Result<()> {
+            let remaining = plan.total - plan.paid;
+            let buyer = plan.buyer;
+            if amount < remaining {
+                plan.paid += amount;
+                plan.next_due += self.installment_interval;
+                self.installment_plans.insert(seat.clone(), plan);
+                self.installment_escrow += amount;
+            } else {
+                let owner = self.owner;
+                self.transfer_from_to(&owner, &buyer, 1)?;
+                self.installment_plans.take(&seat);
+                self.installment_escrow -= plan.paid;
+                self.sell_seat(&seat, buyer, plan.total - plan.fee);
+                self.collect_fee(plan.fee);
+            }
+            self.env().emit_event(InstallmentPaid {
+                buyer,
+                seat,
+                amount,
+                remaining: remaining - amount,
+            });
+            Ok(())
+        }
+
+        /// Forfeits the installment plan of `seat` if it is overdue and returns the
+        /// refund owed to its buyer, to be paid with `pay_out`.
+        fn forfeit_plan(&mut self, seat: &String) -> Vec<(AccountId, Balance)> {
+            let plan = match self.installment_plans.get(seat) {
+                Some(plan) if self.env().block_timestamp() > plan.next_due => plan.clone(),
+                _ => return Vec::new(),
+            };
+            self.installment_plans.take(seat);
+            self.installment_escrow -= plan.paid;
+            let forfeited = plan.paid * self.installment_forfeit_bps as u128 / 10_000;
+            self.record_payment(&plan.buyer, forfeited);
+            let refund = plan.paid - forfeited;
+            self.env().emit_event(InstallmentsForfeited {
+                buyer: plan.buyer,
+                seat: seat.clone(),
+                forfeited,
+                refund,
+            });
+            Vec::from([(plan.buyer, refund)])
+        }
+
+        /// Returns the installment plan of `seat` if its next deadline has not passed.
+        fn active_installment_plan(&self, seat: &String) -> Option<&InstallmentPlan> {
+            self.installment_plans
+                .get(seat)
+                .filter(|plan| self.env().block_timestamp() <= plan.next_due)
+        }
+
+        /// Returns the hold on `seat` if it has not expired yet.
+        fn active_hold(&self, seat: &String) -> Option<&SeatHold> {
+            self.holds
+                .get(seat)
+                .filter(|hold| self.env().block_timestamp() <= hold.until)
+        }
+
+        /// Sets the hold policy: buyers can hold seats for `ttl` milliseconds against a
+        /// `deposit` per seat, of which `bounty` is paid to whoever expires the hold.
+        ///
+        /// A `ttl` of `0` disables new holds. Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_hold_policy(&mut self, ttl: Timestamp, deposit: Balance, bounty: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            if bounty > deposit {
+                return Err(Error::InvalidHoldPolicy)
+            }
+            self.hold_ttl = ttl;
+            self.hold_deposit = deposit;
+            self.hold_bounty = bounty;
+            Ok(())
+        }
+
+        /// Returns the hold time to live, the deposit per seat and the expiry bounty.
+        #[ink(message)]
+        pub fn hold_policy(&self) -> (Timestamp, Balance, Balance) {
+            (self.hold_ttl, self.hold_deposit, self.hold_bounty)
+        }
+
+        /// Returns the hold on `seat`, if any. The hold may have expired.
+        #[ink(message)]
+        pub fn seat_hold(&self, seat: String) -> Option<SeatHold> {
+            self.holds.get(&seat).cloned()
+        }
+
+        /// Holds `seats` for the caller so nobody else can buy them until the hold
+        /// expires. The transferred value must match the hold deposit for all seats.
+        ///
+        /// The deposit is paid back when the caller buys the seats or releases the hold.
+        #[ink(message, payable)]
+        pub fn hold_seats(&mut self, seats: Vec<String>) -> Result<()> {
+            let holder = self.env().caller();
+            self.ensure_can_purchase(&holder)?;
+            if self.hold_ttl == 0 {
+                return Err(Error::HoldsDisabled)
+            }
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| !self.is_seat_free((*seat).clone()))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            let expected = self.hold_deposit * seats.len() as u128;
+            let transferred = self.env().transferred_balance();
+            if transferred != expected {
+                return Err(Error::IncorrectPrice {
+                    expected,
+                    provided: transferred,
+                })
+            }
+            self.non_reentrant(|contract| {
+                let until = contract.env().block_timestamp() + contract.hold_ttl;
+                let mut payouts = Vec::new();
+                for seat in seats.iter() {
+                    // an expired hold left on the seat is settled first
+                    payouts.extend(contract.take_hold(seat, None));
+                    contract.seats_held += 1;
+                    contract.holds.insert(
+                        seat.clone(),
+                        SeatHold {
+                            holder,
+                            until,
+                            deposit: contract.hold_deposit,
+                        },
+                    );
+                }
+                contract.hold_escrow += expected;
+                contract.env().emit_event(SeatsHeld { holder, seats, until });
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Releases the caller's hold on `seat` and pays back its deposit.
+        #[ink(message)]
+        pub fn release_hold(&mut self, seat: String) -> Result<()> {
+            let holder = self.env().caller();
+            if self.holds.get(&seat).map(|hold| hold.holder) != Some(holder) {
+                return Err(Error::NoHold)
+            }
+            self.non_reentrant(|contract| {
+                let payouts = contract.take_hold(&seat, None);
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Releases the holds on `seats` whose time to live has passed, paying the
+        /// caller the hold bounty for each of them. Seats without an expired hold are
+        /// skipped.
+        ///
+        /// Anyone can call this.
+        #[ink(message)]
+        pub fn expire_holds(&mut self, seats: Vec<String>) -> Result<()> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            self.non_reentrant(|contract| {
+                let mut payouts = Vec::new();
+                for seat in seats.iter() {
+                    if contract.holds.get(seat).map_or(false, |hold| now > hold.until) {
+                        payouts.extend(contract.take_hold(seat, Some(caller)));
+                    }
+                }
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Removes the hold on `seat`, if any, and returns the payback of its deposit to
+        /// the holder, to be paid with `pay_out`. If `collector` is given, it receives
+        /// the hold bounty out of the deposit.
+        fn take_hold(&mut self, seat: &String, collector: Option<AccountId>) -> Vec<(AccountId, Balance)> {
+            let hold = match self.holds.take(seat) {
+                Some(hold) => hold,
+                None => return Vec::new(),
+            };
+            self.seats_held -= 1;
+            self.hold_escrow -= hold.deposit;
+            let mut payouts = Vec::new();
+            let bounty = match collector {
+                Some(collector) => {
+                    let bounty = core::cmp::min(self.hold_bounty, hold.deposit);
+                    payouts.push((collector, bounty));
+                    bounty
+                }
+                None => 0,
+            };
+            payouts.push((hold.holder, hold.deposit - bounty));
+            self.env().emit_event(SeatHoldReleased {
+                holder: hold.holder,
+                seat: seat.clone(),
+                expired: collector.is_some(),
+            });
+            payouts
+        }
+
+        /// Transfers the native `payouts` out of the contract, skipping empty ones.
+        ///
+        /// Callers update all of their state first, so no state changes after funds
+        /// leave the contract.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TransferFailed` if a payout cannot be transferred.
+        fn pay_out(&mut self, payouts: Vec<(AccountId, Balance)>) -> Result<()> {
+            for (to, amount) in payouts {
+                if amount > 0 {
+                    self.env().transfer(to, amount).map_err(|_| Error::TransferFailed)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns the identifier of `seat` used as event topic, the BLAKE2 hash of its
+        /// label. Seat events carry both, so the label of a seat id can be looked up in
+        /// the event log.
+        #[ink(message)]
+        pub fn seat_id(&self, seat: String) -> Hash {
+            Hash::from(self.env().hash_bytes::<Blake2x256>(seat.as_bytes()))
+        }
+
+        /// Returns the sale state of each of `seats`, see `seat_status`.
+        #[ink(message)]
+        pub fn seat_statuses(&self, seats: Vec<String>) -> Vec<Option<SeatStatus>> {
+            seats.into_iter().map(|seat| self.seat_status(seat)).collect()
+        }
+
+        /// Returns `true` if `seat` has been pulled out of sale by the owner.
+        #[ink(message)]
+        pub fn is_seat_blocked(&self, seat: String) -> bool {
+            *self.blocked_seats.get(&seat).unwrap_or(&false)
+        }
+
+        /// Returns for each of `seats` whether it is available for purchase.
+        #[ink(message)]
+        pub fn are_seats_free(&self, seats: Vec<String>) -> Vec<bool> {
+            seats.into_iter().map(|seat| self.is_seat_free(seat)).collect()
+        }
+
+        /// Assigns complimentary seats, e.g. for press and sponsors, moving a ticket
+        /// token from the owner to every recipient for each of their seats.
+        ///
+        /// No payment is taken or recorded. Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `DuplicateSeat`, `UnknownSeat` or `SeatsUnavailable` if a seat cannot
+        /// be assigned, and `Blacklisted` if a recipient is blacklisted.
+        #[ink(message)]
+        pub fn airdrop_seats(&mut self, entries: Vec<(AccountId, Vec<String>)>) -> Result<()> {
+            self.ensure_owner()?;
+            if !self.has_seats {
+                return Err(Error::NoSeats)
+            }
+            let seats: Vec<String> = entries.iter().flat_map(|(_, seats)| seats.iter().cloned()).collect();
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| !self.is_seat_free((*seat).clone()))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            if entries.iter().any(|(recipient, _)| self.is_blacklisted(*recipient)) {
+                return Err(Error::Blacklisted)
+            }
+            let owner = self.owner;
+            if self.balance_of_impl(&owner) < seats.len() as u128 {
+                return Err(Error::InsufficientBalance)
+            }
+            for (recipient, seats) in entries {
+                if seats.is_empty() {
+                    continue
+                }
+                self.transfer_from_to(&owner, &recipient, seats.len() as u128)?;
+                for seat in seats.iter() {
+                    self.set_seat_taken(seat, true);
+                    self.seat_owner.insert(seat.clone(), recipient);
+                }
+                self.env().emit_event(SeatsAirdropped { recipient, seats });
+            }
+            self.update_sold_out();
+            Ok(())
+        }
+
+        /// Gifts `seat` and its ticket token from the caller to `recipient`.
+        ///
+        /// An optional `memo`, encrypted for the recipient's wallet, is attached to the
+        /// emitted `SeatGifted` event. The transferred value must match the transfer
+        /// fee, if one is set.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSeatOwner` if the caller does not hold `seat`,
+        /// `DualSignatureRequired` if the seat can only be moved with
+        /// `transfer_seat_signed`, `MemoTooLong` if the memo exceeds `MAX_MEMO_LEN`
+        /// bytes and `IncorrectPrice` if the transfer fee is not paid.
+        #[ink(message, payable)]
+        pub fn gift_seat(&mut self, recipient: AccountId, seat: String, memo: Option<Vec<u8>>) -> Result<()> {
+            let from = self.env().caller();
+            self.ensure_transferable(&from)?;
+            self.ensure_marketplace(&from)?;
+            if self.seat_owner.get(&seat) != Some(&from) {
+                return Err(Error::NotSeatOwner)
+            }
+            if self.requires_dual_signature(seat.clone()) {
+                return Err(Error::DualSignatureRequired)
+            }
+            if memo.as_ref().map_or(false, |memo| memo.len() > MAX_MEMO_LEN) {
+                return Err(Error::MemoTooLong)
+            }
+            let fee = self.transfer_fee_of(&seat, None);
+            let transferred = self.env().transferred_balance();
+            if transferred != fee {
+                return Err(Error::IncorrectPrice {
+                    expected: fee,
+                    provided: transferred,
+                })
+            }
+            self.transfer_from_to(&from, &recipient, 1)?;
+            self.record_payment(&from, fee);
+            self.seat_owner.insert(seat.clone(), recipient);
+            self.seat_sales.take(&seat);
+            self.env().emit_event(SeatGifted {
+                from,
+                to: recipient,
+                seat_id: self.seat_id(seat.clone()),
+                seat,
+                memo,
+            });
+            Ok(())
+        }
+
+        /// Requires the consent of both the holder and the recipient to gift `seat`, e.g.
+        /// for high-value tickets, so nobody can be made to hold it against their will.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_dual_signature(&mut self, seat: String, required: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if !self.seat_taken.contains_key(&seat) {
+                return Err(Error::UnknownSeat(seat))
+            }
+            if required {
+                self.dual_signature_seats.insert(seat, true);
+            } else {
+                self.dual_signature_seats.take(&seat);
+            }
+            Ok(())
+        }
+
+        /// Returns `true` if `seat` can only be gifted with `transfer_seat_signed`.
+        #[ink(message)]
+        pub fn requires_dual_signature(&self, seat: String) -> bool {
+            *self.dual_signature_seats.get(&seat).unwrap_or(&false)
+        }
+
+        /// Moves `seat` and its ticket token from `from` to `to` with the consent of both,
+        /// given as ECDSA signatures over the Blake2x256 hash of the SCALE encoded tuple
+        /// `(SEAT_TRANSFER_DOMAIN, contract, seat, from, to, from_nonce, to_nonce,
+        /// deadline)`, where the nonces are the current `nonce_of` both accounts.
+        ///
+        /// Anyone can submit the transfer. The transferred value must match the transfer
+        /// fee, if one is set.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SignatureExpired` if `deadline` has passed, `NotSeatOwner` if `from`
+        /// does not hold `seat`, `InvalidSignature` if a signature was not made by its
+        /// account and `IncorrectPrice` if the transfer fee is not paid.
+        #[ink(message, payable)]
+        pub fn transfer_seat_signed(
+            &mut self,
+            seat: String,
+            from: AccountId,
+            to: AccountId,
+            deadline: Timestamp,
+            from_signature: [u8; 65],
+            to_signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::SignatureExpired)
+            }
+            self.ensure_transferable(&from)?;
+            self.ensure_marketplace(&from)?;
+            if self.seat_owner.get(&seat) != Some(&from) {
+                return Err(Error::NotSeatOwner)
+            }
+            let from_nonce = self.nonce_of(from);
+            let to_nonce = self.nonce_of(to);
+            let payload = (
+                SEAT_TRANSFER_DOMAIN,
+                self.env().account_id(),
+                &seat,
+                from,
+                to,
+                from_nonce,
+                to_nonce,
+                deadline,
+            );
+            let message_hash = self.env().hash_encoded::<Blake2x256, _>(&payload);
+            if self.recover_signer(&message_hash, &from_signature)? != from
+                || self.recover_signer(&message_hash, &to_signature)? != to
+            {
+                return Err(Error::InvalidSignature)
+            }
+            let fee = self.transfer_fee_of(&seat, None);
+            let transferred = self.env().transferred_balance();
+            if transferred != fee {
+                return Err(Error::IncorrectPrice {
+                    expected: fee,
+                    provided: transferred,
+                })
+            }
+            self.transfer_from_to(&from, &to, 1)?;
+            self.nonces.insert(from, from_nonce + 1);
+            self.nonces.insert(to, to_nonce + 1);
+            self.record_payment(&from, fee);
+            self.seat_owner.insert(seat.clone(), to);
+            self.seat_sales.take(&seat);
+            self.env().emit_event(SeatGifted {
+                from,
+                to,
+                seat_id: self.seat_id(seat.clone()),
+                seat,
+                memo: None,
+            });
+            Ok(())
+        }
+
+        /// Lends `seat` to `to` until `until`, e.g. a season seat for one night. While the
+        /// delegation is active the delegate, not the holder, is checked in on the seat;
+        /// afterwards control reverts to the holder automatically. A new delegation
+        /// replaces the previous one, and the delegation ends when the seat changes hands.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSeatOwner` if the caller does not hold `seat` and
+        /// `InvalidDeadline` if `until` has already passed.
+        #[ink(message)]
+        pub fn delegate_seat(&mut self, seat: String, to: AccountId, until: Timestamp) -> Result<()> {
+            let holder = self.env().caller();
+            if self.seat_owner.get(&seat) != Some(&holder) {
+                return Err(Error::NotSeatOwner)
+            }
+            if until <= self.env().block_timestamp() {
+                return Err(Error::InvalidDeadline)
+            }
+            self.seat_delegations.insert(
+                seat.clone(),
+                SeatDelegation {
+                    holder,
+                    delegate: to,
+                    until,
+                },
+            );
+            self.env().emit_event(SeatDelegated {
+                holder,
+                delegate: to,
+                seat_id: self.seat_id(seat.clone()),
+                seat,
+                until,
+            });
+            Ok(())
+        }
+
+        /// Returns the active delegation of `seat`, if any.
+        #[ink(message)]
+        pub fn seat_delegation(&self, seat: String) -> Option<SeatDelegation> {
+            let delegation = self.seat_delegations.get(&seat)?;
+            if self.seat_owner.get(&seat) != Some(&delegation.holder)
+                || self.env().block_timestamp() > delegation.until
+            {
+                return None
+            }
+            Some(delegation.clone())
+        }
+
+        /// Offers `seat` and its ticket token to `buyer` for `price`, to be accepted with
+        /// `accept_seat_sale` until `deadline`. Replaces an earlier offer of the seat.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSeatOwner` if the caller does not hold `seat`, `DisputeOpen` if
+        /// it is disputed, `InvalidDeadline` if `deadline` has passed,
+        /// `BelowResaleFloor` if `price` is below the resale floor and `EventStarted`
+        /// once the event has started.
+        #[ink(message)]
+        pub fn offer_seat(&mut self, seat: String, buyer: AccountId, price: Balance, deadline: Timestamp) -> Result<()> {
+            let seller = self.env().caller();
+            self.ensure_resale(&seller, &seat)?;
+            if deadline <= self.env().block_timestamp() {
+                return Err(Error::InvalidDeadline)
+            }
+            self.ensure_resale_floor(&seat, price)?;
+            self.seat_sales.insert(
+                seat.clone(),
+                SeatSale {
+                    seller,
+                    buyer,
+                    price,
+                    deadline,
+                },
+            );
+            self.env().emit_event(SeatOffered {
+                seller,
+                buyer,
+                seat,
+                price,
+                deadline,
+            });
+            Ok(())
+        }
+
+        /// Returns the offer of `seat` to a specific buyer, if any. It may have expired.
+        #[ink(message)]
+        pub fn seat_sale(&self, seat: String) -> Option<SeatSale> {
+            self.seat_sales.get(&seat).cloned()
+        }
+
+        /// Withdraws the caller's offer of `seat`.
+        #[ink(message)]
+        pub fn cancel_seat_sale(&mut self, seat: String) -> Result<()> {
+            if self.seat_sales.get(&seat).map(|sale| sale.seller) != Some(self.env().caller()) {
+                return Err(Error::NoSeatSale)
+            }
+            self.seat_sales.take(&seat);
+            Ok(())
+        }
+
+        /// Accepts the offer of `seat` made to the caller. The transferred value must
+        /// match the asking price; it is paid to the seller while the seat and its
+        /// ticket token move to the caller.
+        ///
+        /// From then on refunds and disputes of the seat go to the caller, for at most
+        /// the lower of the asking price and the price originally paid for the seat.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SaleExpired` after the deadline of the offer, `IncorrectPrice` if
+        /// the transferred value does not match and `NotSeatOwner` if the seller no
+        /// longer holds the seat.
+        #[ink(message, payable)]
+        pub fn accept_seat_sale(&mut self, seat: String) -> Result<()> {
+            let buyer = self.env().caller();
+            let sale = self.seat_sales.get(&seat).cloned().ok_or(Error::NoSeatSale)?;
+            if sale.buyer != buyer {
+                return Err(Error::NotSaleBuyer)
+            }
+            if self.env().block_timestamp() > sale.deadline {
+                return Err(Error::SaleExpired)
+            }
+            let transferred = self.env().transferred_balance();
+            if transferred != sale.price {
+                return Err(Error::IncorrectPrice {
+                    expected: sale.price,
+                    provided: transferred,
+                })
+            }
+            let seller = sale.seller;
+            self.ensure_resale(&seller, &seat)?;
+            self.non_reentrant(|contract| contract.settle_resale(seller, buyer, seat, sale.price))
+        }
+
+        /// Sets the share in basis points of in-contract resales, through accepted seat
+        /// sales and offers, that is paid to the organizer.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_royalty(&mut self, royalty_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_royalty_impl(royalty_bps)
+        }
+
+        /// Sets the organizer share of in-contract resales.
+        fn set_royalty_impl(&mut self, royalty_bps: u16) -> Result<()> {
+            if royalty_bps > 10_000 {
+                return Err(Error::InvalidRoyalty)
+            }
+            self.log_admin_action("set_royalty", royalty_bps);
+            self.royalty_bps = royalty_bps;
+            Ok(())
+        }
+
+        /// Returns the share in basis points of in-contract resales paid to the organizer.
+        #[ink(message)]
+        pub fn royalty(&self) -> u16 {
+            self.royalty_bps
+        }
+
+        /// Places an escrowed offer of the transferred value on the sold `seat`, or tops
+        /// up the caller's existing offer. The holder can accept it with `accept_offer`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SeatNotSold` if nobody holds `seat`, `OfferOnOwnSeat` if the caller
+        /// holds it and `BelowResaleFloor` if the offer is below the resale floor.
+        #[ink(message, payable)]
+        pub fn make_offer(&mut self, seat: String) -> Result<()> {
+            let bidder = self.env().caller();
+            self.ensure_can_purchase(&bidder)?;
+            let holder = *self
+                .seat_owner
+                .get(&seat)
+                .ok_or_else(|| Error::SeatNotSold(seat.clone()))?;
+            if holder == bidder {
+                return Err(Error::OfferOnOwnSeat)
+            }
+            let transferred = self.env().transferred_balance();
+            if transferred == 0 {
+                return Err(Error::IncorrectPrice {
+                    expected: 1,
+                    provided: 0,
+                })
+            }
+            let key = (seat.clone(), bidder);
+            let amount = self.seat_offers.get(&key).copied().unwrap_or(0) + transferred;
+            self.ensure_resale_floor(&seat, amount)?;
+            if amount == transferred {
+                let mut bidders = self.seat_bidders.get(&seat).cloned().unwrap_or_default();
+                bidders.push(bidder);
+                self.seat_bidders.insert(seat.clone(), bidders);
+            }
+            self.seat_offers.insert(key, amount);
+            self.offer_escrow += transferred;
+            self.env().emit_event(OfferPlaced { bidder, seat, amount });
+            Ok(())
+        }
+
+        /// Returns the offer of `bidder` on `seat`, or `0` without one.
+        #[ink(message)]
+        pub fn offer_of(&self, seat: String, bidder: AccountId) -> Balance {
+            self.seat_offers.get(&(seat, bidder)).copied().unwrap_or(0)
+        }
+
+        /// Returns the offers on `seat` with their bidders.
+        #[ink(message)]
+        pub fn offers(&self, seat: String) -> Vec<(AccountId, Balance)> {
+            self.seat_bidders
+                .get(&seat)
+                .map(|bidders| {
+                    bidders
+                        .iter()
+                        .map(|bidder| (*bidder, self.offer_of(seat.clone(), *bidder)))
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// Withdraws the caller's offer on `seat` and pays it back.
+        #[ink(message)]
+        pub fn withdraw_offer(&mut self, seat: String) -> Result<()> {
+            let bidder = self.env().caller();
+            if !self.seat_offers.contains_key(&(seat.clone(), bidder)) {
+                return Err(Error::NoOffer)
+            }
+            self.non_reentrant(|contract| {
+                let amount = contract.take_offer(&seat, bidder);
+                contract.env().transfer(bidder, amount).map_err(|_| Error::TransferFailed)?;
+                contract.env().emit_event(OfferWithdrawn { bidder, seat, amount });
+                Ok(())
+            })
+        }
+
+        /// Accepts the offer of `bidder` on `seat`: the seat and its ticket token move
+        /// to the bidder and the escrowed offer, minus the royalty, is paid to the
+        /// caller.
+        ///
+        /// From then on refunds and disputes of the seat go to the bidder, for at most
+        /// the lower of the offer and the price originally paid for the seat.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoOffer` if `bidder` has no offer on `seat`, `BelowResaleFloor` if
+        /// the offer is below the resale floor, and the errors of `offer_seat` if the
+        /// caller cannot resell the seat.
+        #[ink(message)]
+        pub fn accept_offer(&mut self, seat: String, bidder: AccountId) -> Result<()> {
+            let holder = self.env().caller();
+            self.ensure_resale(&holder, &seat)?;
+            let amount = self.seat_offers.get(&(seat.clone(), bidder)).copied().ok_or(Error::NoOffer)?;
+            self.ensure_resale_floor(&seat, amount)?;
+            if self.is_blacklisted(bidder) {
+                return Err(Error::Blacklisted)
+            }
+            self.non_reentrant(|contract| {
+                let amount = contract.take_offer(&seat, bidder);
+                contract.settle_resale(holder, bidder, seat, amount)
+            })
+        }
+
+        /// Sets the fee charged to the sender of any seat transfer after the purchase,
+        /// on top of the royalty on resales. The fee goes to the contract balance.
+        /// `None` removes the fee.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidTransferFee` if a percentage fee exceeds
+        /// `MAX_TRANSFER_FEE_BPS`.
+        #[ink(message)]
+        pub fn set_transfer_fee(&mut self, fee: Option<TransferFee>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_transfer_fee_impl(fee)
+        }
+
+        /// Sets or removes the fee charged on seat transfers.
+        fn set_transfer_fee_impl(&mut self, fee: Option<TransferFee>) -> Result<()> {
+            if let Some(TransferFee::Percentage { bps, .. }) = fee {
+                if bps > MAX_TRANSFER_FEE_BPS {
+                    return Err(Error::InvalidTransferFee)
+                }
+            }
+            self.log_admin_action("set_transfer_fee", fee);
+            self.transfer_fee = fee;
+            Ok(())
+        }
+
+        /// Returns the fee charged on seat transfers after the purchase, if any.
+        #[ink(message)]
+        pub fn transfer_fee(&self) -> Option<TransferFee> {
+            self.transfer_fee
+        }
+
+        /// Sets the minimum price of in-contract resales, through seat sales and offers,
+        /// to prevent dumping below face value. `None` removes the floor.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidResaleFloor` if a floor relative to the face value exceeds
+        /// `10_000` basis points.
+        #[ink(message)]
+        pub fn set_resale_floor(&mut self, floor: Option<ResaleFloor>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_resale_floor_impl(floor)
+        }
+
+        /// Sets or removes the minimum price of in-contract resales.
+        fn set_resale_floor_impl(&mut self, floor: Option<ResaleFloor>) -> Result<()> {
+            if let Some(ResaleFloor::FaceValueBps(bps)) = floor {
+                if bps > 10_000 {
+                    return Err(Error::InvalidResaleFloor)
+                }
+            }
+            self.log_admin_action("set_resale_floor", floor);
+            self.resale_floor = floor;
+            Ok(())
+        }
+
+        /// Returns the minimum price of in-contract resales, if any.
+        #[ink(message)]
+        pub fn resale_floor(&self) -> Option<ResaleFloor> {
+            self.resale_floor
+        }
+
+        /// Returns the lowest native price `seat` can be resold for in the contract.
+        ///
+        /// # Errors
+        ///
+        /// Returns the errors of the price oracle if the floor is relative to the face
+        /// value and prices are in fiat.
+        #[ink(message)]
+        pub fn min_resale_price(&self, seat: String) -> Result<Balance> {
+            match self.resale_floor {
+                None => Ok(0),
+                Some(ResaleFloor::Absolute(minimum)) => Ok(minimum),
+                Some(ResaleFloor::FaceValueBps(bps)) => {
+                    let face_value = self.face_value(&seat) * self.native_rate()?;
+                    Ok(face_value * bps as u128 / 10_000)
+                }
+            }
+        }
+
+        /// Returns `BelowResaleFloor` if `price` is below the minimum resale price of
+        /// `seat`.
+        fn ensure_resale_floor(&self, seat: &String, price: Balance) -> Result<()> {
+            let minimum = self.min_resale_price(seat.clone())?;
+            if price < minimum {
+                return Err(Error::BelowResaleFloor { minimum })
+            }
+            Ok(())
+        }
+
+        /// Returns the transfer fee for moving `seat`, sold for `price` on a resale or
+        /// gifted without one.
+        fn transfer_fee_of(&self, seat: &String, price: Option<Balance>) -> Balance {
+            match self.transfer_fee {
+                None => 0,
+                Some(TransferFee::Flat(fee)) => fee,
+                Some(TransferFee::Percentage { bps, cap }) => {
+                    let price = price.unwrap_or_else(|| {
+                        self.seat_purchases
+                            .get(seat)
+                            .map_or(0, |purchase| purchase.price)
+                    });
+                    (price * bps as u128 / 10_000).min(cap)
+                }
+            }
+        }
+
+        /// Removes the offer of `bidder` on `seat` from escrow and returns its amount.
+        fn take_offer(&mut self, seat: &String, bidder: AccountId) -> Balance {
+            let amount = self.seat_offers.take(&(seat.clone(), bidder)).unwrap_or(0);
+            self.offer_escrow -= amount;
+            let mut bidders = self.seat_bidders.get(seat).cloned().unwrap_or_default();
+            bidders.retain(|account| *account != bidder);
+            if bidders.is_empty() {
+                self.seat_bidders.take(seat);
+            } else {
+                self.seat_bidders.insert(seat.clone(), bidders);
+            }
+            amount
+        }
+
+        /// Moves `seat` and its ticket token from `seller` to `buyer`, paying `price`,
+        /// minus the royalty, to `seller`.
+        ///
+        /// The purchase record of the seat moves to `buyer` as well, so refunds and
+        /// disputes go to the current holder, for no more than it paid.
+        fn settle_resale(&mut self, seller: AccountId, buyer: AccountId, seat: String, price: Balance) -> Result<()> {
+            self.transfer_from_to(&seller, &buyer, 1)?;
+            self.seat_sales.take(&seat);
+            self.seat_owner.insert(seat.clone(), buyer);
+            if let Some(mut purchase) = self.seat_purchases.get(&seat).cloned() {
+                let refundable = core::cmp::min(purchase.price, price);
+                let paid = self.total_paid(purchase.payer);
+                self.total_paid.insert(purchase.payer, paid.saturating_sub(refundable));
+                let paid = self.total_paid(buyer);
+                self.total_paid.insert(buyer, paid + refundable);
+                purchase.payer = buyer;
+                purchase.recipient = buyer;
+                purchase.price = refundable;
+                self.seat_purchases.insert(seat.clone(), purchase);
+            }
+            let royalty = price * self.royalty_bps as u128 / 10_000;
+            self.record_payment(&buyer, royalty);
+            let fee = self.transfer_fee_of(&seat, Some(price)).min(price - royalty);
+            self.record_payment(&seller, fee);
+            self.env().emit_event(SeatResold {
+                seller,
+                buyer,
+                seat_id: self.seat_id(seat.clone()),
+                seat,
+                price,
+                royalty,
+            });
+            self.pay_out(Vec::from([(seller, price - royalty - fee)]))
+        }
+
+        /// Returns an error if `seller` cannot resell `seat` at the moment.
+        fn ensure_resale(&self, seller: &AccountId, seat: &String) -> Result<()> {
+            if self.is_frozen(*seller) {
+                return Err(Error::AccountFrozen)
+            }
+            self.ensure_transferable(seller)?;
+            self.ensure_marketplace(seller)?;
+            if self.has_event_started() {
+                return Err(Error::EventStarted)
+            }
+            if self.seat_owner.get(seat) != Some(seller) {
+                return Err(Error::NotSeatOwner)
+            }
+            if self.balance_of_impl(seller) == 0 {
+                return Err(Error::InsufficientBalance)
+            }
+            if self.disputes.contains_key(seat) {
+                return Err(Error::DisputeOpen)
+            }
+            Ok(())
+        }
+
+        /// Moves one ticket, and optionally `seat`, from `from` to `to`.
+        ///
+        /// This lets verifiers reassign tickets for customer service, also when
+        /// tickets are soulbound.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotVerifier` if the caller is not a verifier, and `NotSeatOwner`
+        /// if `from` does not hold `seat`.
+        #[ink(message)]
+        pub fn reassign_ticket(&mut self, from: AccountId, to: AccountId, seat: Option<String>) -> Result<()> {
+            if !self.is_verifier(self.env().caller()) {
+                return Err(Error::NotVerifier)
+            }
+            if let Some(seat) = &seat {
+                if self.seat_owner.get(seat) != Some(&from) {
+                    return Err(Error::NotSeatOwner)
+                }
+            }
+            self.transfer_from_to(&from, &to, 1)?;
+            if let Some(seat) = seat {
+                self.seat_owner.insert(seat, to);
+            }
+            Ok(())
+        }
+
+        /// Moves `holder` from `old_seat` to the available `new_seat`, releasing `old_seat`.
+        ///
+        /// Only the contract owner or a verifier can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSeatOwner` if `holder` does not hold `old_seat`, `DisputeOpen` if
+        /// `old_seat` is disputed, and `UnknownSeat` or `SeatsUnavailable` if `new_seat`
+        /// cannot be assigned.
+        #[ink(message)]
+        pub fn reassign_seat(
+            &mut self,
+            holder: AccountId,
+            old_seat: String,
+            new_seat: String,
+            reason: ReassignReason,
+        ) -> Result<()> {
+            let by = self.env().caller();
+            if by != self.owner && !self.is_verifier(by) {
+                return Err(Error::NotVerifier)
+            }
+            if self.seat_owner.get(&old_seat) != Some(&holder) {
+                return Err(Error::NotSeatOwner)
+            }
+            if self.disputes.contains_key(&old_seat) {
+                return Err(Error::DisputeOpen)
+            }
+            if !self.seat_taken.contains_key(&new_seat) {
+                return Err(Error::UnknownSeat(new_seat))
+            }
+            if !self.is_seat_free(new_seat.clone()) {
+                return Err(Error::SeatsUnavailable(Vec::from([new_seat])))
+            }
+            self.seat_owner.take(&old_seat);
+            self.set_seat_taken(&old_seat, false);
+            self.set_seat_taken(&new_seat, true);
+            self.seat_owner.insert(new_seat.clone(), holder);
+            if let Some(purchase) = self.seat_purchases.take(&old_seat) {
+                self.seat_purchases.insert(new_seat.clone(), purchase);
+            }
+            self.env().emit_event(SeatReassigned {
+                holder,
+                by,
+                seat_id: self.seat_id(new_seat.clone()),
+                old_seat,
+                new_seat,
+                reason,
+            });
+            Ok(())
+        }
+
+        /// Binds tickets to their holders when `soulbound` is `true`.
+        ///
+        /// While enabled, only verifiers can move tickets between accounts.
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_soulbound(&mut self, soulbound: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.soulbound = soulbound;
+            Ok(())
+        }
+
+        /// Returns `true` if tickets are bound to their holders.
+        #[ink(message)]
+        pub fn is_soulbound(&self) -> bool {
+            self.soulbound
+        }
+
+        /// Approves `marketplace` to move tickets while transfers are restricted.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn add_marketplace(&mut self, marketplace: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.marketplaces.insert(marketplace, true);
+            Ok(())
+        }
+
+        /// Revokes the approval of `marketplace`.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn remove_marketplace(&mut self, marketplace: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.marketplaces.take(&marketplace);
+            Ok(())
+        }
+
+        /// Returns `true` if `marketplace` is approved for resales.
+        #[ink(message)]
+        pub fn is_marketplace(&self, marketplace: AccountId) -> bool {
+            *self.marketplaces.get(&marketplace).unwrap_or(&false)
+        }
+
+        /// Limits `transfer_from` and seat transfers to approved marketplaces when
+        /// `restricted` is `true`.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_restricted_transfers(&mut self, restricted: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.restricted_transfers = restricted;
+            Ok(())
+        }
+
+        /// Returns `true` if transfers are limited to approved marketplaces.
+        #[ink(message)]
+        pub fn is_restricted_transfers(&self) -> bool {
+            self.restricted_transfers
+        }
+
+        /// Adds `seats` to the event and mints a ticket for each of them to the owner.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoSeats` if the contract was deployed without seats, and
+        /// `EmptySeatLabel` or `DuplicateSeat` if a label is empty or already exists,
+        /// and `SeatLabelTooLong` if a label exceeds `MAX_SEAT_LABEL_LEN` bytes.
+        #[ink(message)]
+        pub fn add_seats(&mut self, seats: Vec<String>) -> Result<()> {
+            self.ensure_owner()?;
+            if !self.has_seats {
+                return Err(Error::NoSeats)
+            }
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| self.seat_taken.contains_key(*seat)) {
+                return Err(Error::DuplicateSeat(seat.clone()))
+            }
+            for seat in seats.iter() {
+                self.seat_taken.insert(seat.clone(), false);
+                self.seats.push(seat.clone());
+            }
+            let minted = seats.len() as u128;
+            let owner = self.owner;
+            let owner_balance = self.balance_of_impl(&owner);
+            self.set_balance(&owner, owner_balance + minted);
+            Lazy::set(&mut self.total_supply, *self.total_supply + minted);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(owner),
+                value: minted,
+            });
+            self.env().emit_event(SeatsAdded { seats });
+            self.update_sold_out();
+            Ok(())
+        }
+
+        /// Pulls unsold `seats` out of sale, burning their tickets from the owner's
+        /// inventory.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownSeat` if a seat is not part of the event and
+        /// `SeatsUnavailable` with the seats that are sold or already blocked.
+        #[ink(message)]
+        pub fn block_seats(&mut self, seats: Vec<String>) -> Result<()> {
+            self.ensure_owner()?;
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| !self.is_seat_free((*seat).clone()))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            let owner = self.owner;
+            let burned = seats.len() as u128;
+            let owner_balance = self.balance_of_impl(&owner);
+            if owner_balance < burned {
+                return Err(Error::InsufficientBalance)
+            }
+            for seat in seats.iter() {
+                self.blocked_seats.insert(seat.clone(), true);
+            }
+            self.set_balance(&owner, owner_balance - burned);
+            Lazy::set(&mut self.total_supply, *self.total_supply - burned);
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: None,
+                value: burned,
+            });
+            self.env().emit_event(SeatsBlocked {
+                seats,
+                blocked: true,
+            });
+            self.update_sold_out();
+            Ok(())
+        }
+
+        /// Puts blocked `seats` back on sale, minting their tickets to the owner again.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn unblock_seats(&mut self, seats: Vec<String>) -> Result<()> {
+            self.ensure_owner()?;
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.is_seat_blocked((*seat).clone())) {
+                return Err(Error::SeatNotBlocked(seat.clone()))
+            }
+            for seat in seats.iter() {
+                self.blocked_seats.take(seat);
+            }
+            let owner = self.owner;
+            let minted = seats.len() as u128;
+            let owner_balance = self.balance_of_impl(&owner);
+            self.set_balance(&owner, owner_balance + minted);
+            Lazy::set(&mut self.total_supply, *self.total_supply + minted);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(owner),
+                value: minted,
+            });
+            self.env().emit_event(SeatsBlocked {
+                seats,
+                blocked: false,
+            });
+            self.update_sold_out();
+            Ok(())
+        }
+
+        /// Creates or updates the general admission `zone` with a `capacity` and a
+        /// per-ticket `price`.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidCapacity` if `capacity` is lower than the tickets already
+        /// sold for the zone.
+        #[ink(message)]
+        pub fn set_zone(&mut self, zone: String, capacity: u32, price: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_zone_impl(zone, capacity, price)
+        }
+
+        /// Creates or updates the general admission `zone`.
+        fn set_zone_impl(&mut self, zone: String, capacity: u32, price: Balance) -> Result<()> {
+            let sold = self.zones.get(&zone).map_or(0, |zone| zone.sold);
+            if capacity < sold {
+                return Err(Error::InvalidCapacity)
+            }
+            self.log_admin_action("set_zone", (&zone, capacity, price));
+            if !self.zones.contains_key(&zone) {
+                self.zone_names.push(zone.clone());
+            }
+            self.zones.insert(
+                zone,
+                Zone {
+                    capacity,
+                    sold,
+                    price,
+                },
+            );
+            self.update_sold_out();
+            Ok(())
+        }
+
+        /// Sets the general admission allocation a seated contract sells next to its
+        /// seats: `capacity` tickets at `price` each.
+        ///
+        /// General admission tickets are bought through `purchase_tickets` with an empty
+        /// list of seats. Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoSeats` if the contract was deployed without seats, where all
+        /// tickets are general admission already, and `InvalidCapacity` if `capacity`
+        /// is lower than the tickets already sold.
+        #[ink(message)]
+        pub fn set_general_admission(&mut self, capacity: u32, price: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_general_admission_impl(capacity, price)
+        }
+
+        /// Sets the general admission allocation of a seated contract.
+        fn set_general_admission_impl(&mut self, capacity: u32, price: Balance) -> Result<()> {
+            if !self.has_seats {
+                return Err(Error::NoSeats)
+            }
+            if capacity < self.ga_sold {
+                return Err(Error::InvalidCapacity)
+            }
+            self.ga_capacity = capacity;
+            self.ga_price = price;
+            self.update_sold_out();
+            self.log_admin_action("set_general_admission", (capacity, price));
+            Ok(())
+        }
+
+        /// Returns the general admission allocation of a seated contract as
+        /// `(capacity, sold, price)`.
+        #[ink(message)]
+        pub fn general_admission(&self) -> (u32, u32, Balance) {
+            (self.ga_capacity, self.ga_sold, self.ga_price)
+        }
+
+        /// Lets buyers send more than the price as a tip, which is forwarded to
+        /// `recipient` instead of being rejected with `IncorrectPrice`.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_tips(&mut self, accept: bool, recipient: Option<AccountId>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_tips_impl(accept, recipient)
+        }
+
+        /// Sets whether tips are accepted and who receives them.
+        fn set_tips_impl(&mut self, accept: bool, recipient: Option<AccountId>) -> Result<()> {
+            if accept && recipient.is_none() {
+                return Err(Error::NoTipRecipient)
+            }
+            self.log_admin_action("set_tips", (accept, recipient));
+            self.accept_tips = accept;
+            self.tip_recipient = recipient;
+            Ok(())
+        }
+
+        /// Returns whether tips are accepted.
+        #[ink(message)]
+        pub fn accepts_tips(&self) -> bool {
+            self.accept_tips
+        }
+
+        /// Lets buyers send more than the price, e.g. because of rounding in the UI, and
+        /// returns the change to the caller instead of rejecting the purchase with
+        /// `IncorrectPrice`. Only the price is recorded as revenue.
+        ///
+        /// While tips are accepted, any excess is forwarded as a tip instead. Only the
+        /// contract owner can call this.
+        #[ink(message)]
+        pub fn set_return_change(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.return_change = enabled;
+            Ok(())
+        }
+
+        /// Returns whether change is returned on overpayment.
+        #[ink(message)]
+        pub fn returns_change(&self) -> bool {
+            self.return_change
+        }
+
+        /// Returns the account tips are forwarded to, if any.
+        #[ink(message)]
+        pub fn tip_recipient(&self) -> Option<AccountId> {
+            self.tip_recipient
+        }
+
+        /// Returns the sum of all tips forwarded to the tip recipient.
+        #[ink(message)]
+        pub fn total_donations(&self) -> Balance {
+            self.total_donations
+        }
+
+        /// Starts a lottery for `seats`: accounts enter with a `deposit` until
+        /// `entry_end`, after which the seats are drawn at random among the entrants.
+        ///
+        /// The draw is seeded with a secret the owner commits to up front with its
+        /// `seed_hash`, see `lottery_seed_hash`, and reveals with `draw_lottery`, mixed
+        /// with on-chain randomness from after the last entry. The owner cannot predict
+        /// the outcome while entries can still be added, and if it withholds the seed
+        /// the lottery lapses and all entrants get their deposits back.
+        ///
+        /// The seats are held back from regular sale until the draw. Only the contract
+        /// owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `LotteryActive` if the previous lottery has not been drawn yet, and
+        /// `UnknownSeat` or `SeatsUnavailable` if a seat cannot be allocated.
+        #[ink(message)]
+        pub fn start_lottery(
+            &mut self,
+            seats: Vec<String>,
+            deposit: Balance,
+            entry_end: Timestamp,
+            seed_hash: Hash,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            if !self.has_seats {
+                return Err(Error::NoSeats)
+            }
+            self.lapse_lottery();
+            if self.lottery.as_ref().map_or(false, |lottery| !lottery.drawn) {
+                return Err(Error::LotteryActive)
+            }
+            Self::validate_seats(&seats, seats.len() as u128)?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| !self.is_seat_free((*seat).clone()))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            for seat in seats.iter() {
+                self.lottery_seats.insert(seat.clone(), true);
+            }
+            self.lottery_entrants = Vec::new();
+            self.lottery = Some(Lottery {
+                seats,
+                deposit,
+                entry_end,
+                seed_hash,
+                last_entry_block: self.env().block_number(),
+                drawn: false,
+            });
+            Ok(())
+        }
+
+        /// Returns the current or last lottery, if any.
+        #[ink(message)]
+        pub fn lottery(&self) -> Option<Lottery> {
+            self.lottery.clone()
+        }
+
+        /// Returns the number of accounts that entered the current lottery.
+        #[ink(message)]
+        pub fn lottery_entrant_count(&self) -> u32 {
+            self.lottery_entrants.len() as u32
+        }
+
+        /// Returns the lottery deposit `account` can still claim or has at stake.
+        #[ink(message)]
+        pub fn lottery_deposit_of(&self, account: AccountId) -> Balance {
+            *self.lottery_deposits.get(&account).unwrap_or(&0)
+        }
+
+        /// Enters the caller into the running lottery.
+        ///
+        /// The transferred value must match the lottery deposit.
+        #[ink(message, payable)]
+        pub fn enter_lottery(&mut self) -> Result<()> {
+            let entrant = self.env().caller();
+            self.ensure_can_purchase(&entrant)?;
+            let mut lottery = self.lottery.clone().ok_or(Error::LotteryClosed)?;
+            if lottery.drawn || self.env().block_timestamp() > lottery.entry_end {
+                return Err(Error::LotteryClosed)
+            }
+            if self.lottery_deposits.contains_key(&entrant) {
+                return Err(Error::AlreadyEntered)
+            }
+            let transferred = self.env().transferred_balance();
+            if transferred != lottery.deposit {
+                return Err(Error::IncorrectPrice {
+                    expected: lottery.deposit,
+                    provided: transferred,
+                })
+            }
+            self.lottery_deposits.insert(entrant, transferred);
+            self.lottery_entrants.push(entrant);
+            self.lottery_escrow += transferred;
+            lottery.last_entry_block = self.env().block_number();
+            self.lottery = Some(lottery);
+            self.env().emit_event(LotteryEntered {
+                entrant,
+                deposit: transferred,
+            });
+            Ok(())
+        }
+
+        /// Returns the BLAKE2 hash of the secret lottery `seed` to pass to
+        /// `start_lottery`.
+        #[ink(message)]
+        pub fn lottery_seed_hash(&self, seed: [u8; 32]) -> Hash {
+            Hash::from(self.env().hash_encoded::<Blake2x256, _>(&seed))
+        }
+
+        /// Draws the lottery once its entry window has ended, assigning its seats to
+        /// entrants picked with the revealed `seed`, whose hash the lottery was started
+        /// with, mixed with the final list of entrants and on-chain randomness from a
+        /// block after the last entry.
+        ///
+        /// Winners pay their deposit for the seat. Entrants that cannot receive a ticket
+        /// because they are blacklisted or frozen are skipped and keep their deposit to
+        /// reclaim, as do all entrants when the owner runs out of tickets. Seats left
+        /// over go back on regular sale. Anyone knowing the seed can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `LotteryOpen` until the entry window has ended, `LotteryLapsed` once
+        /// `LOTTERY_REVEAL_WINDOW` has passed since, `InvalidLotterySeed` if `seed`
+        /// does not match the seed hash and `LotteryEntropyPending` while the on-chain
+        /// randomness still stems from before the last entry.
+        #[ink(message)]
+        pub fn draw_lottery(&mut self, seed: [u8; 32]) -> Result<()> {
+            let mut lottery = self.lottery.clone().ok_or(Error::LotteryClosed)?;
+            if lottery.drawn {
+                return Err(Error::LotteryClosed)
+            }
+            let now = self.env().block_timestamp();
+            if now <= lottery.entry_end {
+                return Err(Error::LotteryOpen)
+            }
+            if now > lottery.entry_end + LOTTERY_REVEAL_WINDOW {
+                return Err(Error::LotteryLapsed)
+            }
+            if self.lottery_seed_hash(seed) != lottery.seed_hash {
+                return Err(Error::InvalidLotterySeed)
+            }
+            let (entropy, entropy_block) = self.env().random(&seed);
+            if entropy_block <= lottery.last_entry_block {
+                return Err(Error::LotteryEntropyPending)
+            }
+            let mut entrants = self.lottery_entrants.clone();
+            let seed = self.env().hash_encoded::<Blake2x256, _>(&(seed, &entrants, entropy));
+            let owner = self.owner;
+            let mut tickets = self.balance_of_impl(&owner);
+            let mut nonce = 0u32;
+            for seat in lottery.seats.iter() {
+                self.lottery_seats.take(seat);
+                while tickets > 0 && !entrants.is_empty() {
+                    let draw = self.env().hash_encoded::<Blake2x256, _>(&(seed, nonce));
+                    let draw = draw[..8].iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+                    nonce += 1;
+                    let entrant = entrants.swap_remove((draw % entrants.len() as u64) as usize);
+                    if self.is_blacklisted(entrant) || self.is_frozen(entrant) {
+                        // the entrant keeps its deposit to reclaim
+                        continue
+                    }
+                    self.transfer_from_to(&owner, &entrant, 1)?;
+                    tickets -= 1;
+                    let deposit = self.lottery_deposits.take(&entrant).unwrap_or(0);
+                    self.lottery_escrow -= deposit;
+                    self.sell_seat(seat, entrant, deposit);
+                    self.env().emit_event(LotteryWon {
+                        winner: entrant,
+                        seat: seat.clone(),
+                    });
+                    break
+                }
+            }
+            lottery.drawn = true;
+            self.lottery = Some(lottery);
+            Ok(())
+        }
+
+        /// Closes the lottery if the owner did not draw it within `LOTTERY_REVEAL_WINDOW`
+        /// after its entry window: its seats go back on regular sale and all entrants
+        /// can reclaim their deposits.
+        fn lapse_lottery(&mut self) {
+            let mut lottery = match self.lottery.clone() {
+                Some(lottery) if !lottery.drawn => lottery,
+                _ => return,
+            };
+            if self.env().block_timestamp() <= lottery.entry_end + LOTTERY_REVEAL_WINDOW {
+                return
+            }
+            for seat in lottery.seats.iter() {
+                self.lottery_seats.take(seat);
+            }
+            lottery.drawn = true;
+            self.lottery = Some(lottery);
+        }
+
+        /// Pays back the deposit of a lottery entrant that did not win a seat, once the
+        /// lottery has been drawn or has lapsed.
+        #[ink(message)]
+        pub fn claim_lottery_refund(&mut self) -> Result<()> {
+            let entrant = self.env().caller();
+            self.lapse_lottery();
+            if !self.lottery.as_ref().map_or(false, |lottery| lottery.drawn) {
+                return Err(Error::LotteryNotDrawn)
+            }
+            self.non_reentrant(|contract| {
+                let amount = contract.lottery_deposits.take(&entrant).ok_or(Error::NoDeposit)?;
+                contract.lottery_escrow -= amount;
+                contract.env().transfer(entrant, amount).map_err(|_| Error::TransferFailed)?;
+                contract.env().emit_event(LotteryRefunded { entrant, amount });
+                Ok(())
+            })
+        }
+
+        /// Starts a descending-price auction for `seats`: their price starts at
+        /// `start_price` and decreases by `decay_per_block` every block until it
+        /// reaches `floor_price`. Purchases of these seats are charged the price of
+        /// the block they are made in.
+        ///
+        /// Replaces any running Dutch auction: the seats of the previous auction go back
+        /// to their regular price. Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidAuction` if `floor_price` exceeds `start_price`, and
+        /// `UnknownSeat` or `SeatsUnavailable` if a seat cannot be sold.
+        #[ink(message)]
+        pub fn start_dutch_auction(
+            &mut self,
+            seats: Vec<String>,
+            start_price: Balance,
+            floor_price: Balance,
+            decay_per_block: Balance,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.start_dutch_auction_impl(seats, start_price, floor_price, decay_per_block)
+        }
+
+        /// Starts a Dutch auction for `seats`, replacing any running one.
+        fn start_dutch_auction_impl(
+            &mut self,
+            seats: Vec<String>,
+            start_price: Balance,
+            floor_price: Balance,
+            decay_per_block: Balance,
+        ) -> Result<()> {
+            if floor_price > start_price {
+                return Err(Error::InvalidAuction)
+            }
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| !self.is_seat_free((*seat).clone()))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            self.log_admin_action(
+                "start_dutch_auction",
+                (&seats, start_price, floor_price, decay_per_block),
+            );
+            self.clear_dutch_seats();
+            for seat in seats {
+                self.dutch_seats.insert(seat, true);
+            }
+            self.dutch_auction = Some(DutchAuction {
+                start_price,
+                floor_price,
+                decay_per_block,
+                start_block: self.env().block_number(),
+            });
+            Ok(())
+        }
+
+        /// Ends the running Dutch auction; its seats go back to their regular price.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn end_dutch_auction(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.end_dutch_auction_impl();
+            Ok(())
+        }
+
+        /// Ends the running Dutch auction.
+        fn end_dutch_auction_impl(&mut self) {
+            self.log_admin_action("end_dutch_auction", ());
+            self.dutch_auction = None;
+            self.clear_dutch_seats();
+        }
+
+        /// Returns the running Dutch auction, if any.
+        #[ink(message)]
+        pub fn dutch_auction(&self) -> Option<DutchAuction> {
+            self.dutch_auction.clone()
+        }
+
+        /// Returns the current Dutch auction price, if an auction is running.
+        #[ink(message)]
+        pub fn dutch_price(&self) -> Option<Balance> {
+            self.dutch_auction.as_ref().map(|auction| {
+                let elapsed = (self.env().block_number() - auction.start_block) as Balance;
+                auction
+                    .start_price
+                    .saturating_sub(auction.decay_per_block.saturating_mul(elapsed))
+                    .max(auction.floor_price)
+            })
+        }
+
+        /// Removes all seats from the Dutch auction.
+        fn clear_dutch_seats(&mut self) {
+            let seats: Vec<String> = self.dutch_seats.keys().cloned().collect();
+            for seat in seats.iter() {
+                self.dutch_seats.take(seat);
+            }
+        }
+
+        /// Enables demand-based pricing with `pricing`, starting from the regular price,
+        /// or disables it with `None`. Seats in a Dutch auction are not affected.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidSurgePricing` if `step_seats` or `window` is `0`, or the
+        /// bounds do not include `10_000` basis points.
+        #[ink(message)]
+        pub fn set_surge_pricing(&mut self, pricing: Option<SurgePricing>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_surge_pricing_impl(pricing)
+        }
+
+        /// Enables or disables demand-based pricing.
+        fn set_surge_pricing_impl(&mut self, pricing: Option<SurgePricing>) -> Result<()> {
+            if let Some(pricing) = &pricing {
+                if pricing.step_seats == 0
+                    || pricing.window == 0
+                    || pricing.floor_bps > 10_000
+                    || pricing.ceiling_bps < 10_000
+                {
+                    return Err(Error::InvalidSurgePricing)
+                }
+            }
+            self.log_admin_action("set_surge_pricing", &pricing);
+            if let Some(pricing) = &pricing {
+                self.surge_window = self.env().block_number() / pricing.window;
+            }
+            self.surge_pricing = pricing;
+            self.surge_bps = 10_000;
+            self.surge_sold = 0;
+            Ok(())
+        }
+
+        /// Returns the demand-based pricing policy, if enabled.
+        #[ink(message)]
+        pub fn surge_pricing(&self) -> Option<SurgePricing> {
+            self.surge_pricing.clone()
+        }
+
+        /// Returns the current price multiplier of demand-based pricing in basis points.
+        #[ink(message)]
+        pub fn surge_bps(&self) -> u16 {
+            let pricing = match &self.surge_pricing {
+                Some(pricing) => pricing,
+                None => return 10_000,
+            };
+            let elapsed = self.env().block_number() / pricing.window - self.surge_window;
+            if elapsed == 0 {
+                return self.surge_bps
+            }
+            // every window since the last sale that fell short of a step lowers prices
+            let mut slow_windows = elapsed - 1;
+            if self.surge_sold < pricing.step_seats {
+                slow_windows += 1;
+            }
+            let decrease = (pricing.step_bps as u64).saturating_mul(slow_windows as u64);
+            (self.surge_bps as u64).saturating_sub(decrease).max(pricing.floor_bps as u64) as u16
+        }
+
+        /// Returns the current base ticket price, including demand-based pricing.
+        #[ink(message)]
+        pub fn current_price(&self) -> Balance {
+            self.surge_price(self.price)
+        }
+
+        /// Starts an auction for `seat` that accepts bids of at least `reserve` for
+        /// `duration` milliseconds.
+        ///
+        /// The seat is held back from regular sale until the auction is settled. Only
+        /// the contract owner can call this.
+        #[ink(message)]
+        pub fn start_auction(&mut self, seat: String, reserve: Balance, duration: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            if duration == 0 {
+                return Err(Error::InvalidAuction)
+            }
+            if !self.seat_taken.contains_key(&seat) {
+                return Err(Error::UnknownSeat(seat))
+            }
+            if !self.is_seat_free(seat.clone()) {
+                return Err(Error::SeatsUnavailable(Vec::from([seat])))
+            }
+            self.auctions.insert(
+                seat,
+                Auction {
+                    reserve,
+                    ends_at: self.env().block_timestamp() + duration,
+                    highest_bidder: None,
+                    highest_bid: 0,
+                },
+            );
+            Ok(())
+        }
+
+        /// Returns the running auction for `seat`, if any.
+        #[ink(message)]
+        pub fn auction(&self, seat: String) -> Option<Auction> {
+            self.auctions.get(&seat).cloned()
+        }
+
+        /// Bids the transferred value on the auction for `seat`, refunding the previous
+        /// highest bidder.
+        ///
+        /// # Errors
+        ///
+        /// Returns `BidTooLow` if the bid is below the reserve or does not exceed the
+        /// highest bid.
+        #[ink(message, payable)]
+        pub fn bid(&mut self, seat: String) -> Result<()> {
+            let bidder = self.env().caller();
+            self.ensure_can_purchase(&bidder)?;
+            let mut auction = self.auctions.get(&seat).cloned().ok_or(Error::UnknownAuction)?;
+            if self.env().block_timestamp() > auction.ends_at {
+                return Err(Error::AuctionEnded)
+            }
+            let amount = self.env().transferred_balance();
+            if amount < auction.reserve || (auction.highest_bidder.is_some() && amount <= auction.highest_bid) {
+                return Err(Error::BidTooLow {
+                    minimum: core::cmp::max(auction.reserve, auction.highest_bid + 1),
+                })
+            }
+            self.non_reentrant(|contract| {
+                let mut payouts = Vec::new();
+                if let Some(previous) = auction.highest_bidder {
+                    contract.auction_escrow -= auction.highest_bid;
+                    payouts.push((previous, auction.highest_bid));
+                }
+                auction.highest_bidder = Some(bidder);
+                auction.highest_bid = amount;
+                contract.auction_escrow += amount;
+                contract.auctions.insert(seat.clone(), auction);
+                contract.env().emit_event(BidPlaced { bidder, seat, amount });
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Settles the auction for `seat` once it has ended, assigning the seat and its
+        /// token to the highest bidder. Without bids the seat goes back on regular sale.
+        ///
+        /// If the highest bidder cannot receive a ticket because it is blacklisted or
+        /// frozen, or the owner ran out of tickets, the bid is paid back and the seat goes
+        /// back on regular sale as well. Anyone can call this.
+        #[ink(message)]
+        pub fn settle_auction(&mut self, seat: String) -> Result<()> {
+            let auction = self.auctions.get(&seat).cloned().ok_or(Error::UnknownAuction)?;
+            if self.env().block_timestamp() <= auction.ends_at {
+                return Err(Error::AuctionNotEnded)
+            }
+            let owner = self.owner;
+            let winner = auction.highest_bidder.filter(|bidder| {
+                !self.is_blacklisted(*bidder)
+                    && !self.is_frozen(*bidder)
+                    && !self.is_blacklisted(owner)
+                    && self.balance_of_impl(&owner) > 0
+            });
+            self.non_reentrant(|contract| {
+                let mut payouts = Vec::new();
+                match (winner, auction.highest_bidder) {
+                    (Some(winner), _) => {
+                        contract.transfer_from_to(&owner, &winner, 1)?;
+                        contract.sell_seat(&seat, winner, auction.highest_bid);
+                    }
+                    (None, Some(bidder)) => payouts.push((bidder, auction.highest_bid)),
+                    (None, None) => (),
+                }
+                contract.auction_escrow -= auction.highest_bid;
+                contract.auctions.take(&seat);
+                contract.env().emit_event(AuctionSettled {
+                    winner,
+                    seat,
+                    price: if winner.is_some() { auction.highest_bid } else { 0 },
+                });
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Returns the general admission `zone`, if it exists.
+        #[ink(message)]
+        pub fn zone(&self, zone: String) -> Option<Zone> {
+            self.zones.get(&zone).cloned()
+        }
+
+        /// Returns the names of all general admission zones.
+        #[ink(message)]
+        pub fn zone_names(&self) -> Vec<String> {
+            self.zone_names.clone()
+        }
+
+        /// Purchases `quantity` tickets for the general admission `zone`, minting them to
+        /// the caller.
+        ///
+        /// The transferred value must match the zone price multiplied by `quantity`,
+        /// unless tips are accepted.
+        ///
+        /// Zone tickets are not transferable: the gate admits the buyer, so the tokens
+        /// of tickets that have not been checked in stay with the buyer.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownZone` if the zone does not exist and `ZoneSoldOut` if it does
+        /// not have enough capacity left.
+        #[ink(message, payable)]
+        pub fn purchase_zone(&mut self, zone: String, quantity: u32) -> Result<()> {
+            let buyer = self.env().caller();
+            self.ensure_can_purchase(&buyer)?;
+            let mut info = self
+                .zones
+                .get(&zone)
+                .cloned()
+                .ok_or_else(|| Error::UnknownZone(zone.clone()))?;
+            let remaining = info.capacity - info.sold;
+            if quantity > remaining {
+                return Err(Error::ZoneSoldOut { zone, remaining })
+            }
+            let expected = info.price * quantity as u128 * self.native_rate()?;
+            let fee = self.service_fee_of(expected);
+            let excess = self.check_payment(expected + fee)?;
+            self.throttle(&buyer, &buyer, quantity as u128)?;
+            self.non_reentrant(|contract| {
+                info.sold += quantity;
+                contract.zones.insert(zone.clone(), info);
+                let key = (zone.clone(), buyer);
+                let bought = *contract.zone_tickets.get(&key).unwrap_or(&0);
+                contract.zone_tickets.insert(key, bought + quantity);
+                let unused = contract.unused_zone_ticket_count(&buyer);
+                contract.unused_zone_ticket_count.insert(buyer, unused + quantity);
+                let minted = quantity as u128;
+                let buyer_balance = contract.balance_of_impl(&buyer);
+                contract.set_balance(&buyer, buyer_balance + minted);
+                Lazy::set(&mut contract.total_supply, *contract.total_supply + minted);
+                contract.record_payment(&buyer, expected);
+                contract.collect_fee(fee);
+                contract.env().emit_event(Transfer {
+                    from: None,
+                    to: Some(buyer),
+                    value: minted,
+                });
+                contract.env().emit_event(ZonePurchased {
+                    buyer,
+                    zone,
+                    quantity,
+                    paid: expected,
+                });
+                contract.update_sold_out();
+                contract.settle_excess(buyer, excess)
+            })
+        }
+
+        /// Checks in one zone ticket bought by `holder` at the gate of `zone`. Tickets are
+        /// checked in at most once, and never more than the zone capacity, to enforce
+        /// fire-code limits.
+        ///
+        /// Only a verifier with the `CAN_CHECK_IN` scope can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownZone` if the zone does not exist, `ZoneFull` if its capacity
+        /// has been checked in, `NoZoneTicket` if `holder` has no unused tickets for it
+        /// and `CheckInClosed` once the grace period after the event start has passed.
+        #[ink(message)]
+        pub fn check_in_zone(&mut self, zone: String, holder: AccountId) -> Result<()> {
+            let verifier = self.env().caller();
+            if !self.has_scope(verifier, CAN_CHECK_IN) {
+                return Err(Error::NotVerifier)
+            }
+            if let Some(start) = self.event_start {
+                if self.env().block_timestamp() > start.saturating_add(self.check_in_grace) {
+                    return Err(Error::CheckInClosed)
+                }
+            }
+            let capacity = self
+                .zones
+                .get(&zone)
+                .map(|info| info.capacity)
+                .ok_or_else(|| Error::UnknownZone(zone.clone()))?;
+            let checked_in = self.zone_checked_in_count(zone.clone());
+            if checked_in >= capacity {
+                return Err(Error::ZoneFull(zone))
+            }
+            if self.unused_zone_tickets(zone.clone(), holder) == 0 {
+                return Err(Error::NoZoneTicket(zone))
+            }
+            let key = (zone.clone(), holder);
+            let admitted = *self.zone_admissions.get(&key).unwrap_or(&0);
+            self.zone_admissions.insert(key, admitted + 1);
+            let unused = self.unused_zone_ticket_count(&holder);
+            self.unused_zone_ticket_count.insert(holder, unused - 1);
+            self.zone_checked_in_count.insert(zone.clone(), checked_in + 1);
+            self.env().emit_event(ZoneCheckedIn {
+                holder,
+                verifier,
+                zone,
+                checked_in: checked_in + 1,
+            });
+            Ok(())
+        }
+
+        /// Returns the number of tickets checked in at the gate of `zone`.
+        #[ink(message)]
+        pub fn zone_checked_in_count(&self, zone: String) -> u32 {
+            *self.zone_checked_in_count.get(&zone).unwrap_or(&0)
+        }
+
+        /// Returns the number of tickets `holder` bought for `zone` that have not been
+        /// checked in yet.
+        #[ink(message)]
+        pub fn unused_zone_tickets(&self, zone: String, holder: AccountId) -> u32 {
+            let key = (zone, holder);
+            let bought = *self.zone_tickets.get(&key).unwrap_or(&0);
+            bought - *self.zone_admissions.get(&key).unwrap_or(&0)
+        }
+
+        /// Returns the number of zone tickets of `holder` not checked in yet, across
+        /// all zones.
+        fn unused_zone_ticket_count(&self, holder: &AccountId) -> u32 {
+            *self.unused_zone_ticket_count.get(holder).unwrap_or(&0)
+        }
+
+        /// Creates or updates the general admission `sku` with an `inventory` of tickets
+        /// and a per-ticket `price`.
+        ///
+        /// SKU tickets are sold from the owner's tickets. Only the contract owner can
+        /// call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SeatedContract` if the contract sells seats, and `InvalidCapacity`
+        /// if `inventory` is lower than the tickets already sold under the SKU.
+        #[ink(message)]
+        pub fn set_sku(&mut self, sku: String, inventory: u32, price: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_sku_impl(sku, inventory, price)
+        }
+
+        /// Creates or updates the general admission `sku`.
+        fn set_sku_impl(&mut self, sku: String, inventory: u32, price: Balance) -> Result<()> {
+            if self.has_seats {
+                return Err(Error::SeatedContract)
+            }
+            let sold = self.skus.get(&sku).map_or(0, |sku| sku.sold);
+            if inventory < sold {
+                return Err(Error::InvalidCapacity)
+            }
+            self.log_admin_action("set_sku", (&sku, inventory, price));
+            if !self.skus.contains_key(&sku) {
+                self.sku_names.push(sku.clone());
+            }
+            self.skus.insert(
+                sku,
+                Sku {
+                    inventory,
+                    sold,
+                    price,
+                },
+            );
+            Ok(())
+        }
+
+        /// Returns the general admission `sku`, if it exists.
+        #[ink(message)]
+        pub fn sku(&self, sku: String) -> Option<Sku> {
+            self.skus.get(&sku).cloned()
+        }
+
+        /// Returns the names of all general admission SKUs.
+        #[ink(message)]
+        pub fn sku_names(&self) -> Vec<String> {
+            self.sku_names.clone()
+        }
+
+        /// Purchases `quantity` tickets of the general admission `sku` from the owner.
+        ///
+        /// The transferred value must match the SKU price multiplied by `quantity`,
+        /// unless tips are accepted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownSku` if the SKU does not exist, `SkuSoldOut` if it does not
+        /// have enough inventory left and `SoldOut` if the owner has too few tickets.
+        #[ink(message, payable)]
+        pub fn purchase_sku(&mut self, sku: String, quantity: u32) -> Result<()> {
+            let buyer = self.env().caller();
+            self.ensure_can_purchase(&buyer)?;
+            let mut info = self
+                .skus
+                .get(&sku)
+                .cloned()
+                .ok_or_else(|| Error::UnknownSku(sku.clone()))?;
+            let remaining = info.inventory - info.sold;
+            if quantity > remaining {
+                return Err(Error::SkuSoldOut { sku, remaining })
+            }
+            let expected = info.price * quantity as u128 * self.native_rate()?;
+            let fee = self.service_fee_of(expected);
+            let excess = self.check_payment(expected + fee)?;
+            let owner = self.owner;
+            if self.balance_of_impl(&owner) < quantity as u128 {
+                return Err(Error::SoldOut)
+            }
+            self.throttle(&buyer, &buyer, quantity as u128)?;
+            self.non_reentrant(|contract| {
+                contract.transfer_from_to(&owner, &buyer, quantity as u128)?;
+                info.sold += quantity;
+                let sold_out = info.sold == info.inventory;
+                contract.skus.insert(sku.clone(), info);
+                let revenue = contract.tier_revenue_of(DEFAULT_TIER) + expected;
+                contract.tier_revenue.insert(String::from(DEFAULT_TIER), revenue);
+                contract.record_payment(&buyer, expected);
+                contract.collect_fee(fee);
+                contract.env().emit_event(SkuPurchased {
+                    buyer,
+                    sku: sku.clone(),
+                    quantity,
+                    paid: expected,
+                });
+                if sold_out {
+                    contract.env().emit_event(SkuSoldOut { sku });
+                }
+                contract.update_sold_out();
+                contract.settle_excess(buyer, excess)
+            })
+        }
+
+        /// Creates or updates `tier` with a per-seat `price` and assigns `seats` to it.
+        /// `DEFAULT_TIER` is reserved for seats without a tier and cannot be created.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_tier(&mut self, tier: String, price: Balance, seats: Vec<String>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_tier_impl(tier, price, seats)
+        }
+
+        /// Creates or updates `tier` with a per-seat `price` and assigns `seats` to it.
+        fn set_tier_impl(&mut self, tier: String, price: Balance, seats: Vec<String>) -> Result<()> {
+            if tier == DEFAULT_TIER {
+                return Err(Error::ReservedTier)
+            }
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            self.log_admin_action("set_tier", (&tier, price, &seats));
+            if !self.tiers.contains(&tier) {
+                self.tiers.push(tier.clone());
+            }
+            self.tier_price.insert(tier.clone(), price);
+            for seat in seats {
+                self.seat_tier.insert(seat, tier.clone());
+            }
+            Ok(())
+        }
+
+        /// Sets an explicit `price` for `seat` that takes precedence over its tier price.
+        /// Passing `None` removes the override.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_seat_price(&mut self, seat: String, price: Option<Balance>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_seat_price_impl(seat, price)
+        }
+
+        /// Sets or removes the explicit price of `seat`.
+        fn set_seat_price_impl(&mut self, seat: String, price: Option<Balance>) -> Result<()> {
+            if !self.seat_taken.contains_key(&seat) {
+                return Err(Error::UnknownSeat(seat))
+            }
+            self.log_admin_action("set_seat_price", (&seat, price));
+            match price {
+                Some(price) => self.seat_price_override.insert(seat, price),
+                None => self.seat_price_override.take(&seat),
+            };
+            Ok(())
+        }
+
+        /// Sets the per-seat `price` of an existing `tier`, repricing all of its seats at
+        /// once.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_tier_price(&mut self, tier: String, price: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_tier_price_impl(tier, price)
+        }
+
+        /// Sets the per-seat `price` of an existing `tier`.
+        fn set_tier_price_impl(&mut self, tier: String, price: Balance) -> Result<()> {
+            self.ensure_tier(&tier)?;
+            self.tier_price.insert(tier.clone(), price);
+            self.log_admin_action("set_tier_price", (&tier, price));
+            self.env().emit_event(PricesUpdated {
+                tiers: Vec::from([(tier, price)]),
+            });
+            Ok(())
+        }
+
+        /// Sets the per-seat prices of many existing tiers at once, see
+        /// `set_tier_price`. Either all prices are set or, if a tier does not exist,
+        /// none of them.
+        ///
+        /// A single `PricesUpdated` event is emitted. Only the contract owner can call
+        /// this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownTier` with the first tier that does not exist.
+        #[ink(message)]
+        pub fn set_prices_batch(&mut self, prices: Vec<(String, Balance)>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_prices_batch_impl(prices)
+        }
+
+        /// Sets the per-seat prices of the existing tiers in `prices`.
+        fn set_prices_batch_impl(&mut self, prices: Vec<(String, Balance)>) -> Result<()> {
+            for (tier, _) in prices.iter() {
+                self.ensure_tier(tier)?;
+            }
+            for (tier, price) in prices.iter() {
+                self.tier_price.insert(tier.clone(), *price);
+            }
+            self.log_admin_action("set_prices_batch", &prices);
+            self.env().emit_event(PricesUpdated { tiers: prices });
+            Ok(())
+        }
+
+        /// Returns `UnknownTier` if `tier` does not exist.
+        fn ensure_tier(&self, tier: &String) -> Result<()> {
+            if !self.tier_price.contains_key(tier) {
+                return Err(Error::UnknownTier(tier.clone()))
+            }
+            Ok(())
+        }
+
+        /// Issues a voucher worth `value` in native funds, identified by the hash of its
+        /// code, see `voucher_id`. Issuing an existing voucher again tops it up.
+        ///
+        /// Only the hash is stored, so the code stays secret until it is redeemed in
+        /// `purchase_tickets`. Redeemed value is not paid into the contract, the owner
+        /// bears it. Only the contract owner can call this.
+        #[ink(message)]
+        pub fn mint_voucher(&mut self, voucher: Hash, value: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            let remaining = self.voucher_balance(voucher);
+            self.vouchers.insert(voucher, remaining + value);
+            Ok(())
+        }
+
+        /// Returns the remaining value of the voucher with the given id.
+        #[ink(message)]
+        pub fn voucher_balance(&self, voucher: Hash) -> Balance {
+            self.vouchers.get(&voucher).copied().unwrap_or(0)
+        }
+
+        /// Returns the id of the voucher `code`, the BLAKE2 hash of the code.
+        #[ink(message)]
+        pub fn voucher_id(&self, code: String) -> Hash {
+            Hash::from(self.env().hash_bytes::<Blake2x256>(code.as_bytes()))
+        }
+
+        /// Returns the current price of `seat`, or `None` if it is not part of this event.
+        ///
+        /// The price is denominated in fiat minor units if a price oracle is configured.
+        #[ink(message)]
+        pub fn price_of_seat(&self, seat: String) -> Option<Balance> {
+            if !self.seat_taken.contains_key(&seat) {
+                return None
+            }
+            Some(self.seat_price(&seat))
+        }
+
+                       // little comment
+        #[ink(message,payable)]
+        pub fn clear(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.non_reentrant(Self::clear_impl)
+        }
+
+        /// Withdraws the contract balance to the withdrawal destination.
+        fn clear_impl(&mut self) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            self.ensure_withdrawal_unlocked()?;
+            if self.withdraw_delay > 0 {
+                return Err(Error::WithdrawalTimelocked)
+            }
+            if self.stream.is_some() {
+                return Err(Error::StreamActive)
+            }
+            let (to, amount) = (self.withdrawal_destination(), self.releasable_balance());
+            self.log_admin_action("withdraw", (to, amount));
+            self.withdraw_impl(to, amount)
+        }
+
+        /// Returns the part of the contract balance that is not frozen by disputes.
+        #[ink(message)]
+        pub fn withdrawable_balance(&self) -> Balance {
+            self.contract_balance - self.frozen_funds
+        }
+
+        /// Returns the part of the withdrawable balance the owner can currently take out,
+        /// taking the vesting schedule into account.
+        #[ink(message)]
+        pub fn releasable_balance(&self) -> Balance {
+            if self.refund_mode {
+                return 0
+            }
+            let withdrawable = self.withdrawable_balance().saturating_sub(self.locked_reserve());
+            if self.vesting_percent == 0 {
+                return withdrawable
+            }
+            let now = self.env().block_timestamp();
+            if self.event_end == 0 || now < self.event_end {
+                return 0
+            }
+            let periods = (now - self.event_end) / self.vesting_period;
+            let percent = core::cmp::min(100, periods as u128 * self.vesting_percent as u128);
+            let vested = (self.contract_balance + self.vesting_released) * percent / 100;
+            core::cmp::min(vested.saturating_sub(self.vesting_released), withdrawable)
+        }
+
+        /// Holds back `reserve_bps` basis points of every payment in a refund reserve
+        /// that the owner cannot withdraw until the refund window closes at `until`, so
+        /// refunds stay covered even if the organizer withdraws the rest. Refunds paid out
+        /// draw the reserve down.
+        ///
+        /// Once set, the reserve can only be made stricter. Only the contract owner can
+        /// call this.
+        #[ink(message)]
+        pub fn set_refund_reserve(&mut self, reserve_bps: u16, until: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            if reserve_bps > 10_000 || reserve_bps < self.reserve_bps || until < self.reserve_until {
+                return Err(Error::InvalidReserve)
+            }
+            self.reserve_bps = reserve_bps;
+            self.reserve_until = until;
+            Ok(())
+        }
+
+        /// Returns the share in basis points held back in the refund reserve and when
+        /// the refund window closes.
+        #[ink(message)]
+        pub fn refund_reserve_policy(&self) -> (u16, Timestamp) {
+            (self.reserve_bps, self.reserve_until)
+        }
+
+        /// Returns the part of the withdrawable balance the owner cannot take out
+        /// because it is held back for refunds.
+        #[ink(message)]
+        pub fn locked_reserve(&self) -> Balance {
+            if self.env().block_timestamp() >= self.reserve_until {
+                return 0
+            }
+            core::cmp::min(self.refund_reserve, self.withdrawable_balance())
+        }
+
+        /// Sets a vesting schedule for the proceeds: after the event has ended,
+        /// `percent_per_period` percent of them becomes withdrawable every `period`
+        /// milliseconds.
+        ///
+        /// Once set, the schedule can only be made stricter, so a compromised owner key
+        /// cannot drain the proceeds at once. Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_vesting(&mut self, period: Timestamp, percent_per_period: u8) -> Result<()> {
+            self.ensure_owner()?;
+            if period == 0 || percent_per_period == 0 || percent_per_period > 100 {
+                return Err(Error::InvalidVesting)
+            }
+            if self.vesting_percent > 0 && (percent_per_period > self.vesting_percent || period < self.vesting_period) {
+                return Err(Error::InvalidVesting)
+            }
+            self.vesting_period = period;
+            self.vesting_percent = percent_per_period;
+            Ok(())
+        }
+
+        /// Returns the vesting period and the percentage released per period, if a
+        /// vesting schedule is set.
+        #[ink(message)]
+        pub fn vesting(&self) -> Option<(Timestamp, u8)> {
+            if self.vesting_percent == 0 {
+                return None
+            }
+            Some((self.vesting_period, self.vesting_percent))
+        }
+
+        /// Transfers `amount` of the contract balance to `to`.
+        fn withdraw_impl(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            self.contract_balance -= amount;
+            self.vesting_released += amount;
+            self.env().transfer(to, amount).map_err(|_| Error::TransferFailed)?;
+            self.env().emit_event(Withdrawn { to, amount });
+            Ok(())
+        }
+
+        /// Requests to stream the proceeds to `payout` at `rate_per_block` instead of
+        /// lump-sum withdrawals, which are disabled while a stream is set. Passing `None`
+        /// stops the stream. The change can be executed with `execute_stream_change`
+        /// once the withdrawal delay has passed.
+        ///
+        /// Accrued but unclaimed funds are not carried over when the stream is changed.
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TimelockRequired` while no withdrawal delay is set, see
+        /// `set_withdraw_delay`, and `InvalidStreamRate` if `rate_per_block` exceeds
+        /// `max_stream_rate`.
+        #[ink(message)]
+        pub fn request_stream(&mut self, stream: Option<(AccountId, Balance)>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.request_stream_impl(stream)
+        }
+
+        /// Records a pending change of the payout stream to `stream`.
+        fn request_stream_impl(&mut self, stream: Option<(AccountId, Balance)>) -> Result<()> {
+            if self.withdraw_delay == 0 {
+                return Err(Error::TimelockRequired)
+            }
+            if self.pending_stream.is_some() {
+                return Err(Error::StreamChangePending)
+            }
+            if self.pending_withdrawal.is_some() {
+                return Err(Error::WithdrawalPending)
+            }
+            if stream.map_or(false, |(_, rate_per_block)| rate_per_block > self.max_stream_rate()) {
+                return Err(Error::InvalidStreamRate)
+            }
+            let executable_at = self.env().block_number() + self.withdraw_delay;
+            self.pending_stream = Some(PendingStream {
+                stream,
+                executable_at,
+            });
+            self.log_admin_action("request_stream", stream);
+            self.env().emit_event(StreamChangeRequested {
+                stream,
+                executable_at,
+            });
+            Ok(())
+        }
+
+        /// Executes the pending change of the payout stream once its timelock has passed.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn execute_stream_change(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            let pending = self
+                .pending_stream
+                .clone()
+                .ok_or(Error::NoPendingStreamChange)?;
+            if self.env().block_number() < pending.executable_at {
+                return Err(Error::TimelockNotExpired)
+            }
+            if self.pending_withdrawal.is_some() {
+                return Err(Error::WithdrawalPending)
+            }
+            self.pending_stream = None;
+            let last_claim = self.env().block_number();
+            self.stream = pending.stream.map(|(payout, rate_per_block)| Stream {
+                payout,
+                rate_per_block,
+                last_claim,
+            });
+            self.log_admin_action("execute_stream_change", pending.stream);
+            self.env().emit_event(StreamChanged {
+                stream: pending.stream,
+            });
+            Ok(())
+        }
+
+        /// Cancels the pending change of the payout stream.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn cancel_stream_change(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            let pending = self
+                .pending_stream
+                .take()
+                .ok_or(Error::NoPendingStreamChange)?;
+            self.log_admin_action("cancel_stream_change", pending.stream);
+            Ok(())
+        }
+
+        /// Returns the pending change of the payout stream, if any.
+        #[ink(message)]
+        pub fn pending_stream(&self) -> Option<PendingStream> {
+            self.pending_stream.clone()
+        }
+
+        /// Returns the highest rate per block a stream can be requested at: the
+        /// releasable balance spread over `MIN_STREAM_BLOCKS` blocks.
+        #[ink(message)]
+        pub fn max_stream_rate(&self) -> Balance {
+            self.releasable_balance() / MIN_STREAM_BLOCKS as Balance
+        }
+
+        /// Returns the payout stream, if any.
+        #[ink(message)]
+        pub fn stream(&self) -> Option<Stream> {
+            self.stream.clone()
+        }
+
+        /// Returns the amount `claim_stream` would currently pay out.
+        #[ink(message)]
+        pub fn claimable_stream(&self) -> Balance {
+            self.stream.as_ref().map_or(0, |stream| {
+                let blocks = (self.env().block_number() - stream.last_claim) as Balance;
+                core::cmp::min(stream.rate_per_block.saturating_mul(blocks), self.releasable_balance())
+            })
+        }
+
+        /// Pays the proceeds streamed since the last claim out to the payout address.
+        ///
+        /// Anyone can call this.
+        #[ink(message)]
+        pub fn claim_stream(&mut self) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            self.ensure_withdrawal_unlocked()?;
+            let mut stream = self.stream.clone().ok_or(Error::NoStream)?;
+            let amount = self.claimable_stream();
+            self.non_reentrant(|contract| {
+                stream.last_claim = contract.env().block_number();
+                let payout = stream.payout;
+                contract.stream = Some(stream);
+                contract.withdraw_impl(payout, amount)
+            })
+        }
+
+        /// Sets the number of blocks a withdrawal has to wait between `request_withdraw`
+        /// and `execute_withdraw`. While the delay is non-zero, `clear` is disabled.
+        ///
+        /// The delay can only be increased, so a compromised owner key cannot remove it.
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_withdraw_delay(&mut self, delay: BlockNumber) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_withdraw_delay_impl(delay)
+        }
+
+        /// Sets the withdrawal delay to `delay`, see `set_withdraw_delay`.
+        fn set_withdraw_delay_impl(&mut self, delay: BlockNumber) -> Result<()> {
+            if delay < self.withdraw_delay {
+                return Err(Error::InvalidDelay)
+            }
+            self.withdraw_delay = delay;
+            Ok(())
+        }
+
+        /// Returns the number of blocks a withdrawal has to wait.
+        #[ink(message)]
+        pub fn withdraw_delay(&self) -> BlockNumber {
+            self.withdraw_delay
+        }
+
+        /// Requests a withdrawal of `amount` that can be executed once the withdrawal
+        /// delay has passed.
+        ///
+        /// A `WithdrawalRequested` event is emitted so buyers can react in time.
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn request_withdraw(&mut self, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.request_withdraw_impl(amount)
+        }
+
+        /// Records a pending withdrawal of `amount`.
+        fn request_withdraw_impl(&mut self, amount: Balance) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            self.ensure_withdrawal_unlocked()?;
+            if self.pending_withdrawal.is_some() {
+                return Err(Error::WithdrawalPending)
+            }
+            if self.stream.is_some() {
+                return Err(Error::StreamActive)
+            }
+            if amount > self.releasable_balance() {
+                return Err(Error::InsufficientBalance)
+            }
+            let executable_at = self.env().block_number() + self.withdraw_delay;
+            self.pending_withdrawal = Some(PendingWithdrawal {
+                amount,
+                executable_at,
+            });
+            self.log_admin_action("request_withdraw", amount);
+            self.env().emit_event(WithdrawalRequested {
+                amount,
+                executable_at,
+            });
+            Ok(())
+        }
+
+        /// Executes the pending withdrawal once its timelock has passed.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn execute_withdraw(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.non_reentrant(Self::execute_withdraw_impl)
+        }
+
+        /// Executes the pending withdrawal, see `execute_withdraw`.
+        fn execute_withdraw_impl(&mut self) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            self.ensure_withdrawal_unlocked()?;
+            let pending = self
+                .pending_withdrawal
+                .clone()
+                .ok_or(Error::NoPendingWithdrawal)?;
+            if self.env().block_number() < pending.executable_at {
+                return Err(Error::TimelockNotExpired)
+            }
+            if pending.amount > self.releasable_balance() {
+                return Err(Error::InsufficientBalance)
+            }
+            self.pending_withdrawal = None;
+            let to = self.withdrawal_destination();
+            self.log_admin_action("withdraw", (to, pending.amount));
+            self.withdraw_impl(to, pending.amount)
+        }
+
+        /// Cancels the pending withdrawal.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn cancel_withdraw(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            let pending = self
+                .pending_withdrawal
+                .take()
+                .ok_or(Error::NoPendingWithdrawal)?;
+            self.log_admin_action("cancel_withdraw", pending.amount);
+            self.env().emit_event(WithdrawalCancelled {
+                amount: pending.amount,
+            });
+            Ok(())
+        }
+
+        /// Requests that withdrawals go to `treasury`, e.g. a DAO contract, instead of
+        /// the owner, so custody of the proceeds is decoupled from the operational owner
+        /// key. `None` makes withdrawals go to the owner again. The change can be executed
+        /// with `execute_treasury_change` once the withdrawal delay has passed.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `TimelockRequired` while no withdrawal delay is set, see
+        /// `set_withdraw_delay`, and `TreasuryChangePending` if a change is already
+        /// pending.
+        #[ink(message)]
+        pub fn request_treasury(&mut self, treasury: Option<AccountId>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.request_treasury_impl(treasury)
+        }
+
+        /// Records a pending change of the treasury to `treasury`.
+        fn request_treasury_impl(&mut self, treasury: Option<AccountId>) -> Result<()> {
+            if self.withdraw_delay == 0 {
+                return Err(Error::TimelockRequired)
+            }
+            if self.pending_treasury.is_some() {
+                return Err(Error::TreasuryChangePending)
+            }
+            let executable_at = self.env().block_number() + self.withdraw_delay;
+            self.pending_treasury = Some(PendingTreasury {
+                treasury,
+                executable_at,
+            });
+            self.log_admin_action("request_treasury", treasury);
+            self.env().emit_event(TreasuryChangeRequested {
+                treasury,
+                executable_at,
+            });
+            Ok(())
+        }
+
+        /// Executes the pending treasury change once its timelock has passed.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn execute_treasury_change(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            let pending = self
+                .pending_treasury
+                .clone()
+                .ok_or(Error::NoPendingTreasuryChange)?;
+            if self.env().block_number() < pending.executable_at {
+                return Err(Error::TimelockNotExpired)
+            }
+            self.pending_treasury = None;
+            self.treasury = pending.treasury;
+            self.log_admin_action("execute_treasury_change", pending.treasury);
+            self.env().emit_event(TreasuryChanged {
+                treasury: pending.treasury,
+            });
+            Ok(())
+        }
+
+        /// Cancels the pending treasury change.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn cancel_treasury_change(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            let pending = self
+                .pending_treasury
+                .take()
+                .ok_or(Error::NoPendingTreasuryChange)?;
+            self.log_admin_action("cancel_treasury_change", pending.treasury);
+            Ok(())
+        }
+
+        /// Returns the treasury receiving withdrawals, if set.
+        #[ink(message)]
+        pub fn treasury(&self) -> Option<AccountId> {
+            self.treasury
+        }
+
+        /// Returns the pending treasury change, if any.
+        #[ink(message)]
+        pub fn pending_treasury(&self) -> Option<PendingTreasury> {
+            self.pending_treasury.clone()
+        }
+
+        /// Returns the account withdrawals are paid to: the treasury if set, or else the
+        /// owner.
+        #[ink(message)]
+        pub fn withdrawal_destination(&self) -> AccountId {
+            self.treasury.unwrap_or(self.owner)
+        }
+
+        /// Sets the timestamp at which the event ends.
+        ///
+        /// Once set, the end can only be postponed, so `finalize_event` cannot be
+        /// brought forward. Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_event_end(&mut self, event_end: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            if event_end < self.event_end {
+                return Err(Error::InvalidEventEnd)
+            }
+            self.event_end = event_end;
+            Ok(())
+        }
+
+        /// Returns the timestamp at which the event ends, `0` if not set.
+        #[ink(message)]
+        pub fn event_end(&self) -> Timestamp {
+            self.event_end
+        }
+
+        /// Returns `true` if the event has been wound down.
+        #[ink(message)]
+        pub fn is_finalized(&self) -> bool {
+            self.finalized
+        }
+
+        /// Winds down the event after it has ended: stops all purchases and resales and
+        /// sweeps the releasable balance to the withdrawal destination, see
+        /// `finalize_sweep`. Proceeds that are not swept can still be withdrawn or
+        /// streamed afterwards.
+        ///
+        /// If `terminate` is `true` the contract is terminated afterwards, paying its
+        /// storage deposit to the withdrawal destination. Only the contract owner can
+        /// call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OpenDisputes` if `terminate` is `true` while disputes are open and
+        /// `FundsOutstanding` while escrowed deposits, bids or offers, accrued service
+        /// fees, the refund reserve or proceeds that are not swept are still held.
+        #[ink(message)]
+        pub fn finalize_event(&mut self, terminate: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.non_reentrant(|contract| contract.finalize_event_impl(terminate))
+        }
+
+        /// Returns the funds held for others than the withdrawal destination: escrowed
+        /// deposits, bids and offers, accrued service fees and the locked refund reserve.
+        /// Terminating the contract would sweep them to the withdrawal destination.
+        fn outstanding_funds(&self) -> Balance {
+            self.lottery_escrow
+                + self.auction_escrow
+                + self.hold_escrow
+                + self.commit_escrow
+                + self.proposal_escrow
+                + self.installment_escrow
+                + self.offer_escrow
+                + self.fees_accrued
+                + self.locked_reserve()
+        }
+
+        /// Returns the amount `finalize_event` sweeps: nothing while a stream is set, the
+        /// matured pending withdrawal while withdrawals are timelocked, and else the
+        /// releasable balance.
+        fn finalize_sweep(&self) -> Balance {
+            if self.stream.is_some() {
+                return 0
+            }
+            if self.withdraw_delay == 0 {
+                return self.releasable_balance()
+            }
+            match &self.pending_withdrawal {
+                Some(pending)
+                    if self.env().block_number() >= pending.executable_at
+                        && pending.amount <= self.releasable_balance() =>
+                {
+                    pending.amount
+                }
+                _ => 0,
+            }
+        }
+
+        /// Winds down the event, see `finalize_event`.
+        fn finalize_event_impl(&mut self, terminate: bool) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            if self.finalized {
+                return Err(Error::EventFinalized)
+            }
+            if self.event_end == 0 || self.env().block_timestamp() < self.event_end {
+                return Err(Error::EventNotEnded)
+            }
+            if terminate && self.frozen_funds > 0 {
+                return Err(Error::OpenDisputes)
+            }
+            let swept = self.finalize_sweep();
+            // terminating would hand unvested, unstreamed or timelocked proceeds over at once
+            if terminate && (self.outstanding_funds() > 0 || self.contract_balance > swept) {
+                return Err(Error::FundsOutstanding)
+            }
+            if swept > 0 && self.withdraw_delay > 0 {
+                self.execute_withdraw_impl()?;
+            } else if swept > 0 {
+                self.clear_impl()?;
+            }
+            self.finalized = true;
+            self.log_admin_action("finalize_event", terminate);
+            self.env().emit_event(Finalized {
+                swept,
+                terminated: terminate,
+            });
+            if terminate {
+                self.env().terminate_contract(self.withdrawal_destination())
+            }
+            Ok(())
+        }
+
+        /// Locks owner withdrawals until the event end has passed or `threshold`
+        /// verifiers have called `attest_event_held`, so buyer funds cannot be taken out
+        /// before the event took place. A `threshold` of `0` disables the lock.
+        ///
+        /// Once set, the threshold can only be increased, so a compromised owner key
+        /// cannot lift the lock. Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_held_threshold(&mut self, threshold: u32) -> Result<()> {
+            self.ensure_owner()?;
+            if threshold < self.held_threshold {
+                return Err(Error::InvalidThreshold)
+            }
+            self.held_threshold = threshold;
+            Ok(())
+        }
+
+        /// Returns the number of attestations that unlock withdrawals, `0` if they
+        /// are not locked.
+        #[ink(message)]
+        pub fn held_threshold(&self) -> u32 {
+            self.held_threshold
+        }
+
+        /// Attests that the event was held, counting towards unlocking withdrawals.
+        ///
+        /// Only a verifier can call this, once.
+        #[ink(message)]
+        pub fn attest_event_held(&mut self) -> Result<()> {
+            let verifier = self.env().caller();
+            if !self.is_verifier(verifier) {
+                return Err(Error::NotVerifier)
+            }
+            if self.held_attestations.contains_key(&verifier) {
+                return Err(Error::AlreadyAttested)
+            }
+            self.held_attestations.insert(verifier, true);
+            self.env().emit_event(EventHeldAttested {
+                verifier,
+                attestations: self.held_attestations.len(),
+            });
+            Ok(())
+        }
+
+        /// Returns the number of verifiers that attested that the event was held.
+        #[ink(message)]
+        pub fn held_attestations(&self) -> u32 {
+            self.held_attestations.len()
+        }
+
+        /// Returns `true` if owner withdrawals are unlocked: no threshold is set, the
+        /// event end has passed or enough verifiers attested that the event was held.
+        #[ink(message)]
+        pub fn is_event_held(&self) -> bool {
+            self.held_threshold == 0
+                || (self.event_end > 0 && self.env().block_timestamp() >= self.event_end)
+                || self.held_attestations.len() >= self.held_threshold
+        }
+
+        /// Configures who can switch the contract to refund-only mode with
+        /// `emergency_refund`: the `guardian` on its own, or `quorum` verifiers together.
+        /// A `quorum` of `0` leaves it to the guardian.
+        ///
+        /// Only the contract owner can call this, before refund-only mode is active.
+        #[ink(message)]
+        pub fn set_emergency_policy(&mut self, guardian: Option<AccountId>, quorum: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_emergency_policy_impl(guardian, quorum)
+        }
+
+        /// Sets the emergency guardian and the verifier quorum.
+        fn set_emergency_policy_impl(&mut self, guardian: Option<AccountId>, quorum: u32) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            self.log_admin_action("set_emergency_policy", (guardian, quorum));
+            self.emergency_guardian = guardian;
+            self.emergency_quorum = quorum;
+            Ok(())
+        }
+
+        /// Returns the emergency guardian and the verifier quorum, see
+        /// `set_emergency_policy`.
+        #[ink(message)]
+        pub fn emergency_policy(&self) -> (Option<AccountId>, u32) {
+            (self.emergency_guardian, self.emergency_quorum)
+        }
+
+        /// Signals an emergency. Once the guardian calls this, or the verifier quorum is
+        /// reached, the contract switches to refund-only mode for good: purchases and
+        /// owner withdrawals stop, open disputes are dropped and payers can reclaim
+        /// their payments with `claim_emergency_refund`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotGuardian` if the caller is neither the guardian nor a verifier
+        /// while a quorum is set, and `AlreadySignalled` on a second signal.
+        #[ink(message)]
+        pub fn emergency_refund(&mut self) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            let caller = self.env().caller();
+            if self.emergency_guardian == Some(caller) {
+                self.activate_refund_mode(caller);
+                return Ok(())
+            }
+            if self.emergency_quorum == 0 || !self.is_verifier(caller) {
+                return Err(Error::NotGuardian)
+            }
+            if self.emergency_signals.contains_key(&caller) {
+                return Err(Error::AlreadySignalled)
+            }
+            self.emergency_signals.insert(caller, true);
+            let signals = self.emergency_signals.len();
+            self.env().emit_event(EmergencySignalled {
+                verifier: caller,
+                signals,
+            });
+            if signals >= self.emergency_quorum {
+                self.activate_refund_mode(caller);
+            }
+            Ok(())
+        }
+
+        /// Returns `true` if the contract is in refund-only mode.
+        #[ink(message)]
+        pub fn is_refund_mode(&self) -> bool {
+            self.refund_mode
+        }
+
+        /// Pays the caller back everything they paid for tickets, as far as the
+        /// contract balance covers it.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotRefundMode` unless the contract is in refund-only mode and
+        /// `NothingToRefund` if the caller has no payments to reclaim.
+        #[ink(message)]
+        pub fn claim_emergency_refund(&mut self) -> Result<()> {
+            if !self.refund_mode {
+                return Err(Error::NotRefundMode)
+            }
+            let payer = self.env().caller();
+            let amount = core::cmp::min(self.total_paid(payer), self.contract_balance);
+            if amount == 0 {
+                return Err(Error::NothingToRefund)
+            }
+            self.non_reentrant(|contract| {
+                contract.record_refund(&payer, amount);
+                contract.total_paid.take(&payer);
+                contract.env().transfer(payer, amount).map_err(|_| Error::TransferFailed)?;
+                contract.env().emit_event(EmergencyRefunded { payer, amount });
+                Ok(())
+            })
+        }
+
+        /// Switches the contract to refund-only mode, dropping the pending withdrawal
+        /// and open disputes, whose payers are refunded through their payments.
+        fn activate_refund_mode(&mut self, by: AccountId) {
+            self.refund_mode = true;
+            self.log_admin_action("emergency_refund", ());
+            self.pending_withdrawal = None;
+            let disputed: Vec<String> = self.disputes.keys().cloned().collect();
+            for seat in disputed.iter() {
+                self.disputes.take(seat);
+            }
+            self.frozen_funds = 0;
+            self.env().emit_event(RefundModeActivated { by });
+        }
+
+        /// Configures a price oracle so that `price` and tier prices are denominated in fiat
+        /// minor units (e.g. euro cents) and converted into native funds at purchase time.
+        ///
+        /// Purchases are rejected while the oracle rate is older than `max_age`
+        /// milliseconds. Passing `None` switches back to native pricing.
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_oracle(&mut self, oracle: Option<AccountId>, max_age: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_oracle_impl(oracle, max_age);
+            Ok(())
+        }
+
+        /// Sets or removes the price oracle.
+        fn set_oracle_impl(&mut self, oracle: Option<AccountId>, max_age: Timestamp) {
+            self.log_admin_action("set_oracle", (oracle, max_age));
+            self.oracle = oracle;
+            self.oracle_max_age = max_age;
+        }
+
+        /// Returns the configured price oracle and the maximum age of its rate.
+        #[ink(message)]
+        pub fn oracle(&self) -> (Option<AccountId>, Timestamp) {
+            (self.oracle, self.oracle_max_age)
+        }
+
+        /// Configures an external registry of banned resellers, shared between venues.
+        ///
+        /// Purchases query its `is_flagged(account) -> bool` message for the paying and
+        /// receiving accounts and are refused for flagged accounts, or if the registry
+        /// cannot be queried. Passing `None` disables the check.
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_scalper_registry(&mut self, registry: Option<AccountId>) -> Result<()> {
+            self.ensure_owner()?;
+            self.scalper_registry = registry;
+            Ok(())
+        }
+
+        /// Returns the configured scalper registry, if any.
+        #[ink(message)]
+        pub fn scalper_registry(&self) -> Option<AccountId> {
+            self.scalper_registry
+        }
+
+        /// Sets the time in milliseconds after a purchase during which its buyer can raise
+        /// a dispute.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_dispute_window(&mut self, window: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.dispute_window = window;
+            Ok(())
+        }
+
+        /// Returns the purchase record of `seat`, if it has been sold.
+        #[ink(message)]
+        pub fn seat_purchase(&self, seat: String) -> Option<SeatPurchase> {
+            self.seat_purchases.get(&seat).cloned()
+        }
+
+        /// Sets the time in milliseconds after a purchase during which verifiers can
+        /// reverse it with `reverse_purchase`. A window of `0` disables reversals.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_reversal_window(&mut self, window: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.reversal_window = window;
+            Ok(())
+        }
+
+        /// Returns the time after a purchase during which it can be reversed.
+        #[ink(message)]
+        pub fn reversal_window(&self) -> Timestamp {
+            self.reversal_window
+        }
+
+        /// Reverses the purchase of `seats` for fraud control: the price paid for each
+        /// seat is returned to its payer, the holder's ticket is burned, a fresh one is
+        /// issued to the owner and the seat becomes available again.
+        ///
+        /// Only the contract owner or a verifier with the `CAN_REFUND` scope can call this,
+        /// within the reversal window after each purchase.
+        ///
+        /// # Errors
+        ///
+        /// Returns `DuplicateSeat` if a seat is listed twice, `SeatNotSold` if a seat has
+        /// not been bought, `ReversalWindowClosed` if its reversal window has passed,
+        /// `DisputeOpen` if it is disputed and `InsufficientBalance` if the refunds are
+        /// not covered by the withdrawable balance.
+        #[ink(message)]
+        pub fn reverse_purchase(&mut self, seats: Vec<String>, reason: CancelReason) -> Result<()> {
+            let by = self.env().caller();
+            if by != self.owner && !self.has_scope(by, CAN_REFUND) {
+                return Err(Error::NotVerifier)
+            }
+            let now = self.env().block_timestamp();
+            let mut purchases = Vec::new();
+            for (i, seat) in seats.iter().enumerate() {
+                if seats[..i].contains(seat) {
+                    return Err(Error::DuplicateSeat(seat.clone()))
+                }
+                let purchase = self
+                    .seat_purchases
+                    .get(seat)
+                    .cloned()
+                    .ok_or_else(|| Error::SeatNotSold(seat.clone()))?;
+                if now > purchase.purchased_at + self.reversal_window {
+                    return Err(Error::ReversalWindowClosed)
+                }
+                if self.disputes.contains_key(seat) {
+                    return Err(Error::DisputeOpen)
+                }
+                purchases.push(purchase);
+            }
+            let refund: Balance = purchases.iter().map(|purchase| purchase.price).sum();
+            if refund > self.withdrawable_balance() {
+                return Err(Error::InsufficientBalance)
+            }
+            self.non_reentrant(|contract| {
+                let mut payouts = Vec::new();
+                for (seat, purchase) in seats.iter().zip(purchases.iter()) {
+                    if let Some(holder) = contract.seat_owner.take(seat) {
+                        contract.reissue_ticket(&holder);
+                    }
+                    contract.release_seat(seat);
+                    contract.record_refund(&purchase.payer, purchase.price);
+                    payouts.push((purchase.payer, purchase.price));
+                }
+                contract.env().emit_event(PurchaseReversed {
+                    by,
+                    seats,
+                    refund,
+                    reason,
+                });
+                contract.pay_out(payouts)
+            })
+        }
+
+        /// Burns a ticket of `holder`, if it still has one, and issues a fresh one to the
+        /// owner in its place.
+        fn reissue_ticket(&mut self, holder: &AccountId) {
+            let balance = self.balance_of_impl(holder);
+            if balance == 0 {
+                return
+            }
+            self.set_balance(holder, balance - 1);
+            self.env().emit_event(Transfer {
+                from: Some(*holder),
+                to: None,
+                value: 1,
+            });
+            let owner = self.owner;
+            let owner_balance = self.balance_of_impl(&owner);
+            self.set_balance(&owner, owner_balance + 1);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(owner),
+                value: 1,
+            });
+        }
+
+        /// Disputes the purchase of `seat`, freezing the amount paid for it until a
+        /// verifier resolves the dispute.
+        ///
+        /// Only the account that paid for the seat can call this, within the dispute
+        /// window after the purchase.
+        #[ink(message)]
+        pub fn raise_dispute(&mut self, seat: String) -> Result<()> {
+            self.ensure_no_refund_mode()?;
+            let buyer = self.env().caller();
+            let purchase = self.seat_purchases.get(&seat).cloned().ok_or(Error::NotBuyer)?;
+            if purchase.payer != buyer {
+                return Err(Error::NotBuyer)
+            }
+            if self.env().block_timestamp() > purchase.purchased_at + self.dispute_window {
+                return Err(Error::DisputeWindowClosed)
+            }
+            if self.disputes.contains_key(&seat) {
+                return Err(Error::DisputeOpen)
+            }
+            if purchase.price > self.withdrawable_balance() {
+                return Err(Error::InsufficientBalance)
+            }
+            self.disputes.insert(seat.clone(), purchase.price);
+            self.frozen_funds += purchase.price;
+            self.env().emit_event(DisputeRaised {
+                buyer,
+                seat,
+                amount: purchase.price,
+            });
+            Ok(())
+        }
+
+        /// Returns the amount frozen by an open dispute on `seat`, if any.
+        #[ink(message)]
+        pub fn dispute_of(&self, seat: String) -> Option<Balance> {
+            self.disputes.get(&seat).copied()
+        }
+
+        /// Resolves the dispute on `seat`.
+        ///
+        /// If `refund` is `true` the frozen amount is paid back to the buyer, the seat is
+        /// released for sale and its ticket is returned to the owner. Otherwise the frozen
+        /// funds are released to the contract balance. Only a verifier with the
+        /// `CAN_REFUND` scope can call this.
+        #[ink(message)]
+        pub fn resolve_dispute(&mut self, seat: String, refund: bool) -> Result<()> {
+            let arbiter = self.env().caller();
+            if !self.has_scope(arbiter, CAN_REFUND) {
+                return Err(Error::NotVerifier)
+            }
+            self.non_reentrant(|contract| contract.resolve_dispute_impl(arbiter, seat, refund))
+        }
+
+        /// Resolves the dispute on `seat`, see `resolve_dispute`.
+        fn resolve_dispute_impl(&mut self, arbiter: AccountId, seat: String, refund: bool) -> Result<()> {
+            let amount = self.disputes.take(&seat).ok_or(Error::NoDispute)?;
+            self.frozen_funds -= amount;
+            let purchase = self.seat_purchases.get(&seat).cloned().ok_or(Error::NoDispute)?;
+            if refund {
+                self.release_seat(&seat);
+                self.record_refund(&purchase.payer, amount);
+                self.env().transfer(purchase.payer, amount).map_err(|_| Error::TransferFailed)?;
+            }
+            self.env().emit_event(DisputeResolved {
+                buyer: purchase.payer,
+                arbiter,
+                seat,
+                amount,
+                refunded: refund,
+            });
+            Ok(())
+        }
+
+        /// Attests that `account` passed KYC when `attested` is `true`, or revokes the
+        /// attestation otherwise.
+        ///
+        /// Only a verifier with the `CAN_ATTEST_KYC` scope can call this.
+        #[ink(message)]
+        pub fn attest(&mut self, account: AccountId, attested: bool) -> Result<()> {
+            let verifier = self.env().caller();
+            if !self.has_scope(verifier, CAN_ATTEST_KYC) {
+                return Err(Error::NotVerifier)
+            }
+            if attested {
+                self.kyc_attested.insert(account, true);
+            } else {
+                self.kyc_attested.take(&account);
+            }
+            self.env().emit_event(KycAttestation {
+                account,
+                verifier,
+                attested,
+            });
+            Ok(())
+        }
+
+        /// Returns `true` if a verifier attested that `account` passed KYC.
+        #[ink(message)]
+        pub fn is_kyc_attested(&self, account: AccountId) -> bool {
+            *self.kyc_attested.get(&account).unwrap_or(&false)
+        }
+
+        /// Attests that `account` is eligible for accessible seats when `eligible` is
+        /// `true`, or revokes the attestation otherwise.
+        ///
+        /// Only a verifier with the `CAN_ATTEST_KYC` scope can call this.
+        #[ink(message)]
+        pub fn attest_accessibility(&mut self, account: AccountId, eligible: bool) -> Result<()> {
+            let verifier = self.env().caller();
+            if !self.has_scope(verifier, CAN_ATTEST_KYC) {
+                return Err(Error::NotVerifier)
+            }
+            if eligible {
+                self.accessibility_attested.insert(account, true);
+            } else {
+                self.accessibility_attested.take(&account);
+            }
+            self.env().emit_event(AccessibilityAttestation {
+                account,
+                verifier,
+                eligible,
+            });
+            Ok(())
+        }
+
+        /// Returns `true` if a verifier attested that `account` is eligible for accessible
+        /// seats.
+        #[ink(message)]
+        pub fn is_accessibility_eligible(&self, account: AccountId) -> bool {
+            *self.accessibility_attested.get(&account).unwrap_or(&false)
+        }
+
+        /// Attests the concession `category` of `account`, e.g. after checking a student
+        /// ID. Attesting `Adult` removes a concession.
+        ///
+        /// Only a verifier with the `CAN_ATTEST_KYC` scope can call this.
+        #[ink(message)]
+        pub fn attest_category(&mut self, account: AccountId, category: BuyerCategory) -> Result<()> {
+            let verifier = self.env().caller();
+            if !self.has_scope(verifier, CAN_ATTEST_KYC) {
+                return Err(Error::NotVerifier)
+            }
+            if category == BuyerCategory::Adult {
+                self.buyer_categories.take(&account);
+            } else {
+                self.buyer_categories.insert(account, category);
+            }
+            self.env().emit_event(CategoryAttested {
+                account,
+                verifier,
+                category,
+            });
+            Ok(())
+        }
+
+        /// Returns the attested concession category of `account`.
+        #[ink(message)]
+        pub fn buyer_category(&self, account: AccountId) -> BuyerCategory {
+            *self.buyer_categories.get(&account).unwrap_or(&BuyerCategory::Adult)
+        }
+
+        /// Sets the price of tickets bought for accounts of `category` to `price_bps`
+        /// basis points of the regular price, e.g. `5_000` for half price.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidCategoryPrice` if `price_bps` exceeds `10_000`.
+        #[ink(message)]
+        pub fn set_category_price(&mut self, category: BuyerCategory, price_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_category_price_impl(category, price_bps)
+        }
+
+        /// Sets the price of `category` in basis points of the regular price.
+        fn set_category_price_impl(&mut self, category: BuyerCategory, price_bps: u16) -> Result<()> {
+            if price_bps > 10_000 {
+                return Err(Error::InvalidCategoryPrice)
+            }
+            self.log_admin_action("set_category_price", (category, price_bps));
+            self.category_price_bps.insert(category, price_bps);
+            Ok(())
+        }
+
+        /// Returns the price of `category` in basis points of the regular price.
+        #[ink(message)]
+        pub fn category_price(&self, category: BuyerCategory) -> u16 {
+            *self.category_price_bps.get(&category).unwrap_or(&10_000)
+        }
+
+        /// Marks `seats` as accessible when `accessible` is `true`, or unmarks them
+        /// otherwise. Accessible seats stay visible in availability queries, but can only
+        /// be bought for accounts with an accessibility eligibility attestation.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownSeat` if a seat is not part of the event.
+        #[ink(message)]
+        pub fn set_accessible_seats(&mut self, seats: Vec<String>, accessible: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            for seat in seats {
+                if accessible {
+                    self.accessible_seats.insert(seat, true);
+                } else {
+                    self.accessible_seats.take(&seat);
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns `true` if `seat` is reserved for holders with an accessibility
+        /// eligibility attestation.
+        #[ink(message)]
+        pub fn is_accessible_seat(&self, seat: String) -> bool {
+            *self.accessible_seats.get(&seat).unwrap_or(&false)
+        }
+
+        /// Limits purchases to KYC attested accounts when `required` is `true`.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_kyc_required(&mut self, required: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.kyc_required = required;
+            Ok(())
+        }
+
+        /// Limits every account to `limit` tickets per window of `window` blocks, as a
+        /// first line of defense against sniping bots. A `limit` of `0` disables the
+        /// throttle.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_throttle(&mut self, limit: u32, window: BlockNumber) -> Result<()> {
+            self.ensure_owner()?;
+            if limit > 0 && window == 0 {
+                return Err(Error::InvalidThrottle)
+            }
+            self.throttle_limit = limit;
+            self.throttle_window = window;
+            Ok(())
+        }
+
+        /// Returns the purchase throttle as `(limit, window)`.
+        #[ink(message)]
+        pub fn throttle_config(&self) -> (u32, BlockNumber) {
+            (self.throttle_limit, self.throttle_window)
+        }
+
+        /// Charges a service fee of `fee_bps` basis points on top of every purchase, which
+        /// is kept apart from ticket revenue and can only be withdrawn by `collector`.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_service_fee(&mut self, fee_bps: u16, collector: Option<AccountId>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_service_fee_impl(fee_bps, collector)
+        }
+
+        /// Sets the service fee and its collector.
+        fn set_service_fee_impl(&mut self, fee_bps: u16, collector: Option<AccountId>) -> Result<()> {
+            if fee_bps > 10_000 || (fee_bps > 0 && collector.is_none()) {
+                return Err(Error::InvalidFee)
+            }
+            self.log_admin_action("set_service_fee", (fee_bps, collector));
+            self.fee_bps = fee_bps;
+            self.fee_collector = collector;
+            Ok(())
+        }
+
+        /// Returns the service fee in basis points and the fee collector.
+        #[ink(message)]
+        pub fn service_fee(&self) -> (u16, Option<AccountId>) {
+            (self.fee_bps, self.fee_collector)
+        }
+
+        /// Returns the total amount of service fees collected.
+        #[ink(message)]
+        pub fn fee_collected(&self) -> Balance {
+            self.fee_collected
+        }
+
+        /// Returns the service fees that have not been withdrawn yet.
+        #[ink(message)]
+        pub fn fees_accrued(&self) -> Balance {
+            self.fees_accrued
+        }
+
+        /// Transfers the accrued service fees to the fee collector.
+        ///
+        /// Only the fee collector can call this.
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self) -> Result<()> {
+            let collector = self.env().caller();
+            if self.fee_collector != Some(collector) {
+                return Err(Error::NotFeeCollector)
+            }
+            self.non_reentrant(|contract| {
+                let amount = contract.fees_accrued;
+                contract.fees_accrued = 0;
+                contract.env().transfer(collector, amount).map_err(|_| Error::TransferFailed)?;
+                contract.env().emit_event(FeesWithdrawn { collector, amount });
+                Ok(())
+            })
+        }
+
+        /// Limits purchases to accounts with an on-chain identity judgement, checked
+        /// through the runtime chain extension function `extension`. The function takes
+        /// an `AccountId` and returns a `bool`. Passing `None` disables the check.
+        ///
+        /// Only the contract owner can call this.
+        #[ink(message)]
+        pub fn set_identity_extension(&mut self, extension: Option<u32>) -> Result<()> {
+            self.ensure_owner()?;
+            self.identity_extension = extension;
+            Ok(())
+        }
+
+        /// Returns the chain extension function used to check buyer identities, if any.
+        #[ink(message)]
+        pub fn identity_extension(&self) -> Option<u32> {
+            self.identity_extension
+        }
+
+        /// Returns `true` if purchases are limited to KYC attested accounts.
+        #[ink(message)]
+        pub fn is_kyc_required(&self) -> bool {
+            self.kyc_required
+        }
+
+        /// Blocks `account` from purchasing, receiving and transferring tickets.
+        ///
+        /// Only the contract owner or a verifier can call this.
+        #[ink(message)]
+        pub fn blacklist(&mut self, account: AccountId) -> Result<()> {
+            self.set_blacklisted(account, true)
+        }
+
+        /// Removes `account` from the blacklist.
+        ///
+        /// Only the contract owner or a verifier can call this.
+        #[ink(message)]
+        pub fn unblacklist(&mut self, account: AccountId) -> Result<()> {
+            self.set_blacklisted(account, false)
+        }
+
+        /// Returns `true` if `account` is blacklisted.
+        #[ink(message)]
+        pub fn is_blacklisted(&self, account: AccountId) -> bool {
+            *self.blacklist.get(&account).unwrap_or(&false)
+        }
+
+        /// Adds `account` to or removes it from the blacklist.
+        fn set_blacklisted(&mut self, account: AccountId, blacklisted: bool) -> Result<()> {
+            let by = self.env().caller();
+            if by != self.owner && !self.is_verifier(by) {
+                return Err(Error::NotVerifier)
+            }
+            if blacklisted {
+                self.blacklist.insert(account, true);
+            } else {
+                self.blacklist.take(&account);
+            }
+            self.env().emit_event(BlacklistUpdated {
+                account,
+                by,
+                blacklisted,
+            });
+            Ok(())
+        }
+
+        /// Freezes the tickets of `account` pending a fraud investigation: it cannot
+        /// transfer or resell them, while it can still buy and receive tickets. Unlike
+        /// the blacklist, the owner and verifiers can still move its tickets, e.g. to
+        /// cancel them.
+        ///
+        /// Only the contract owner or a verifier can call this.
+        #[ink(message)]
+        pub fn freeze(&mut self, account: AccountId) -> Result<()> {
+            self.set_frozen(account, true)
+        }
+
+        /// Lifts the freeze of `account`.
+        ///
+        /// Only the contract owner or a verifier can call this.
+        #[ink(message)]
+        pub fn unfreeze(&mut self, account: AccountId) -> Result<()> {
+            self.set_frozen(account, false)
+        }
+
+        /// Returns `true` if `account` is frozen.
+        #[ink(message)]
+        pub fn is_frozen(&self, account: AccountId) -> bool {
+            *self.frozen.get(&account).unwrap_or(&false)
+        }
+
+        /// Freezes or unfreezes `account`.
+        fn set_frozen(&mut self, account: AccountId, frozen: bool) -> Result<()> {
+            let by = self.env().caller();
+            if by != self.owner && !self.is_verifier(by) {
+                return Err(Error::NotVerifier)
+            }
+            if frozen {
+                self.frozen.insert(account, true);
+            } else {
+                self.frozen.take(&account);
+            }
+            self.env().emit_event(FreezeUpdated { account, by, frozen });
+            Ok(())
+        }
+
+        /// Returns up to `limit` entries of the admin log of privileged actions, such as
+        /// price, verifier and withdrawal changes, skipping the first `offset` entries.
+        #[ink(message)]
+        pub fn admin_log(&self, offset: u64, limit: u64) -> Vec<AdminLogEntry> {
+            (offset..self.admin_log_len.min(offset.saturating_add(limit)))
+                .filter_map(|index| self.admin_log.get(&index).cloned())
+                .collect()
+        }
+
+        /// Returns the number of entries in the admin log.
+        #[ink(message)]
+        pub fn admin_log_len(&self) -> u64 {
+            self.admin_log_len
+        }
+
+        /// Returns the version of the storage layout.
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version
+        }
+
+        /// Returns a summary of this deployment so clients can check they are talking
+        /// to a compatible contract.
+        #[ink(message)]
+        pub fn contract_info(&self) -> ContractInfo {
+            ContractInfo {
+                version: String::from(CONTRACT_VERSION),
+                storage_version: self.storage_version,
+                deployed_at: self.deployed_at,
+                owner: self.owner,
+                price: self.price,
+                total_supply: *self.total_supply,
+                has_seats: self.has_seats,
+            }
+        }
+
+        /// Sets the name, venue, date and image URI of the event, so wallets can display
+        /// a proper ticket.
+        ///
+        /// Only the contract owner can call this, and only before sales open.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SalesOpen` once tickets have left the owner.
+        #[ink(message)]
+        pub fn set_event_metadata(&mut self, metadata: EventMetadata) -> Result<()> {
+            self.ensure_owner()?;
+            if self.balance_of_impl(&self.owner) < *self.total_supply {
+                return Err(Error::SalesOpen)
+            }
+            self.event_metadata = Some(metadata);
+            Ok(())
+        }
+
+        /// Returns the description of the event, if the owner has set it.
+        #[ink(message)]
+        pub fn event_metadata(&self) -> Option<EventMetadata> {
+            self.event_metadata.clone()
+        }
+
+        /// Sets the timestamp at which the event starts, or clears it with `None`.
+        ///
+        /// Purchases and marketplace resales are rejected from `start` on, while holders
+        /// can be checked in until `check_in_grace` after it. Only the contract owner can
+        /// call this.
+        #[ink(message)]
+        pub fn set_event_start(&mut self, start: Option<Timestamp>, check_in_grace: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.event_start = start;
+            self.check_in_grace = check_in_grace;
+            Ok(())
+        }
+
+        /// Returns the timestamp at which the event starts, if set.
+        #[ink(message)]
+        pub fn event_start(&self) -> Option<Timestamp> {
+            self.event_start
+        }
+
+        /// Returns the time after the event start during which holders can still be
+        /// checked in.
+        #[ink(message)]
+        pub fn check_in_grace(&self) -> Timestamp {
+            self.check_in_grace
+        }
+
+        /// Sets the number of sessions, e.g. festival days, each ticket grants entry to.
+        /// Sessions are numbered from `0`. Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownSession` if `count` is `0`.
+        #[ink(message)]
+        pub fn set_session_count(&mut self, count: u32) -> Result<()> {
+            self.ensure_owner()?;
+            if count == 0 {
+                return Err(Error::UnknownSession(0))
+            }
+            self.session_count = count;
+            Ok(())
+        }
+
+        /// Returns the number of sessions each ticket grants entry to.
+        #[ink(message)]
+        pub fn session_count(&self) -> u32 {
+            self.session_count.max(1)
+        }
+
+        /// Adds a date starting at `start` to the recurring series of this event, e.g. a
+        /// weekly show, and returns its id. Every date offers all seats of the contract,
+        /// while inventory, purchases and check-ins are tracked per date.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoSeats` if the contract has no seats and `InvalidDeadline` if
+        /// `start` has already passed.
+        #[ink(message)]
+        pub fn add_series_date(&mut self, start: Timestamp) -> Result<u32> {
+            self.ensure_owner()?;
+            if !self.has_seats {
+                return Err(Error::NoSeats)
+            }
+            if start <= self.env().block_timestamp() {
+                return Err(Error::InvalidDeadline)
+            }
+            let date_id = self.series_date_count;
+            self.series_dates.insert(date_id, start);
+            self.series_date_count += 1;
+            Ok(date_id)
+        }
+
+        /// Returns the start timestamps of the series dates, indexed by date id.
+        #[ink(message)]
+        pub fn series_dates(&self) -> Vec<Timestamp> {
+            (0..self.series_date_count)
+                .filter_map(|date_id| self.series_dates.get(&date_id).copied())
+                .collect()
+        }
+
+        /// Sells `seats` for the series date `date_id` to `to`. The caller pays the seat
+        /// prices plus the service fee, as for `purchase_tickets`, and the purchase counts
+        /// towards the throttle and demand-based pricing. No ticket tokens are issued:
+        /// the holders are recorded per date, see `series_seat_owner`, so series tickets
+        /// cannot be transferred or resold. The organizer can cancel them with
+        /// `cancel_series_ticket`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownDate` if the date does not exist, `EventStarted` once it has
+        /// started, `UnknownSeat` or `DuplicateSeat` for invalid seats,
+        /// `SeatsUnavailable` if seats are blocked or already sold for the date,
+        /// `AccessibilityRequired` if `to` may not hold an accessible seat,
+        /// `IncorrectPrice` if the payment does not match and `RateLimited` if the
+        /// purchase exceeds the throttle.
+        #[ink(message, payable)]
+        pub fn purchase_series_seats(&mut self, date_id: u32, to: AccountId, seats: Vec<String>) -> Result<()> {
+            let payer = self.env().caller();
+            self.ensure_can_purchase(&payer)?;
+            if to != payer {
+                self.ensure_not_flagged(&to)?;
+            }
+            let start = *self.series_dates.get(&date_id).ok_or(Error::UnknownDate(date_id))?;
+            if self.env().block_timestamp() >= start {
+                return Err(Error::EventStarted)
+            }
+            if seats.is_empty() {
+                return Err(Error::NoSeats)
+            }
+            for (i, seat) in seats.iter().enumerate() {
+                if !self.seat_taken.contains_key(seat) {
+                    return Err(Error::UnknownSeat(seat.clone()))
+                }
+                if seats[..i].contains(seat) {
+                    return Err(Error::DuplicateSeat(seat.clone()))
+                }
+            }
+            let unavailable: Vec<String> = seats
+                .iter()
+                .filter(|seat| !self.is_series_seat_available(date_id, (*seat).clone()))
+                .cloned()
+                .collect();
+            if !unavailable.is_empty() {
+                return Err(Error::SeatsUnavailable(unavailable))
+            }
+            self.ensure_accessibility(&to, &seats)?;
+            let rate = self.native_rate()?;
+            let category = self.buyer_category(to);
+            let expected = self.concession_payment(seats.len() as Balance, &seats, rate, category);
+            let fee = self.service_fee_of(expected);
+            let excess = self.check_payment(expected + fee)?;
+            self.throttle(&payer, &to, seats.len() as Balance)?;
+
+            self.non_reentrant(|contract| {
+                let purchased_at = contract.env().block_timestamp();
+                for seat in seats.iter() {
+                    let price = contract.concession_price(contract.seat_price(seat) * rate, category);
+                    contract.series_seat_owner.insert((date_id, seat.clone()), to);
+                    contract.series_purchases.insert(
+                        (date_id, seat.clone()),
+                        SeatPurchase {
+                            payer,
+                            recipient: to,
+                            price,
+                            purchased_at,
+                            category,
+                        },
+                    );
+                }
+                let sold = contract.series_sold_count(date_id);
+                contract.series_sold_count.insert(date_id, sold + seats.len() as u32);
+                contract.record_revenue(seats.len() as Balance, &seats, rate, category);
+                contract.record_payment(&payer, expected);
+                contract.collect_fee(fee);
+                contract.env().emit_event(SeriesSeatsPurchased {
+                    payer,
+                    recipient: to,
+                    date_id,
+                    seats: seats.clone(),
+                    paid: expected,
+                });
+                // prices adjust for the next purchase
+                contract.record_surge_sales(seats.len() as Balance);
+                contract.settle_excess(payer, excess)
+            })
+        }
+
+        /// Cancels the ticket for `seat` on the series date `date_id`, e.g. after fraud
+        /// or a sale made in error. The recorded purchase price is refunded to the payer
+        /// from the contract balance and the seat becomes available for the date again.
+        ///
+        /// Only the contract owner or a verifier with the `CAN_REFUND` scope can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SeatNotSold` if nobody holds `seat` for the date and
+        /// `InsufficientBalance` if the refund is not covered by the withdrawable
+        /// balance.
+        #[ink(message)]
+        pub fn cancel_series_ticket(&mut self, date_id: u32, seat: String, reason: CancelReason) -> Result<()> {
+            let by = self.env().caller();
+            if by != self.owner && !self.has_scope(by, CAN_REFUND) {
+                return Err(Error::NotVerifier)
+            }
+            let key = (date_id, seat.clone());
+            let holder = *self
+                .series_seat_owner
+                .get(&key)
+                .ok_or_else(|| Error::SeatNotSold(seat.clone()))?;
+            let purchase = self.series_purchases.get(&key).cloned();
+            let refund = purchase.as_ref().map_or(0, |purchase| purchase.price);
+            if refund > self.withdrawable_balance() {
+                return Err(Error::InsufficientBalance)
+            }
+            self.non_reentrant(|contract| {
+                contract.series_seat_owner.take(&key);
+                contract.series_purchases.take(&key);
+                contract.series_checked_in.take(&key);
+                let sold = contract.series_sold_count(date_id);
+                contract.series_sold_count.insert(date_id, sold - 1);
+                if let Some(purchase) = purchase {
+                    contract.record_refund(&purchase.payer, refund);
+                    contract.env().transfer(purchase.payer, refund).map_err(|_| Error::TransferFailed)?;
+                }
+                contract.env().emit_event(SeriesTicketCancelled {
+                    holder,
+                    by,
+                    date_id,
+                    seat,
+                    refund,
+                    reason,
+                });
+                Ok(())
+            })
+        }
+
+        /// Returns the holder of `seat` for the series date `date_id`, if it has been sold.
+        #[ink(message)]
+        pub fn series_seat_owner(&self, date_id: u32, seat: String) -> Option<AccountId> {
+            self.series_seat_owner.get(&(date_id, seat)).copied()
+        }
+
+        /// Returns `true` if `seat` can be bought for the series date `date_id`: the date
+        /// and seat exist, and the seat is neither blocked nor sold for the date.
+        #[ink(message)]
+        pub fn is_series_seat_available(&self, date_id: u32, seat: String) -> bool {
+            self.series_dates.contains_key(&date_id)
+                && self.seat_taken.contains_key(&seat)
+                && !self.is_seat_blocked(seat.clone())
+                && !self.series_seat_owner.contains_key(&(date_id, seat))
+        }
+
+        /// Returns the number of seats sold for the series date `date_id`.
+        #[ink(message)]
+        pub fn series_sold_count(&self, date_id: u32) -> u32 {
+            *self.series_sold_count.get(&date_id).unwrap_or(&0)
+        }
+
+        /// Checks in the holder of `seat` for the series date `date_id`.
+        ///
+        /// Only a verifier with the `CAN_CHECK_IN` scope can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownDate` if the date does not exist, `SeatNotSold` if nobody
+        /// holds `seat` for the date, `AlreadyCheckedIn` if its holder has been checked
+        /// in before and `CheckInClosed` once the grace period after the date start has
+        /// passed.
+        #[ink(message)]
+        pub fn check_in_series(&mut self, date_id: u32, seat: String) -> Result<()> {
+            let verifier = self.env().caller();
+            if !self.has_scope(verifier, CAN_CHECK_IN) {
+                return Err(Error::NotVerifier)
+            }
+            let start = *self.series_dates.get(&date_id).ok_or(Error::UnknownDate(date_id))?;
+            if self.env().block_timestamp() > start.saturating_add(self.check_in_grace) {
+                return Err(Error::CheckInClosed)
+            }
+            let holder = self
+                .series_seat_owner(date_id, seat.clone())
+                .ok_or_else(|| Error::SeatNotSold(seat.clone()))?;
+            let key = (date_id, seat.clone());
+            if self.series_checked_in.contains_key(&key) {
+                return Err(Error::AlreadyCheckedIn)
+            }
+            self.series_checked_in.insert(key, true);
+            self.env().emit_event(SeriesSeatCheckedIn {
+                holder,
+                verifier,
+                date_id,
+                seat,
+            });
+            Ok(())
+        }
+
+        /// Returns `true` if the holder of `seat` has been checked in for the series date
+        /// `date_id`.
+        #[ink(message)]
+        pub fn is_series_checked_in(&self, date_id: u32, seat: String) -> bool {
+            self.series_checked_in.contains_key(&(date_id, seat))
+        }
+
+        /// Returns `true` once the event start time has been reached.
+        #[ink(message)]
+        pub fn has_event_started(&self) -> bool {
+            self.event_start
+                .map_or(false, |start| self.env().block_timestamp() >= start)
+        }
+
+        /// Migrates the storage layout to `STORAGE_VERSION` after the contract code
+        /// has been upgraded.
+        ///
+        /// Only the contract owner can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownStorageVersion` if the storage is at a version this code
+        /// cannot migrate from. Migrations up to that version stay applied.
+        #[ink(message)]
+        pub fn migrate_storage(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            let from = self.storage_version;
+            if from >= STORAGE_VERSION {
+                return Err(Error::AlreadyMigrated)
+            }
+            while self.storage_version < STORAGE_VERSION {
+                self.migrate_from(self.storage_version)?;
+                self.storage_version += 1;
+            }
+            self.env().emit_event(StorageMigrated {
+                from,
+                to: STORAGE_VERSION,
+            });
+            Ok(())
+        }
+
+        /// Transforms the storage layout of `version` into the layout of `version + 1`.
+        fn migrate_from(&mut self, version: u32) -> Result<()> {
+            match version {
+                // Version 1 is the first versioned layout and is written by the
+                // constructor. Code from before storage versioning has no
+                // `storage_version` field and cannot be migrated.
+                // Version 2 caches the sold, held and checked in seat counts.
+                1 => {
+                    self.seats_sold = self
+                        .seats
+                        .iter()
+                        .filter(|seat| *self.seat_taken.get(*seat).unwrap_or(&false))
+                        .count() as u32;
+                    self.seats_held = self.holds.len();
+                    self.checked_in_count = self.checked_in.len();
+                }
+                _ => return Err(Error::UnknownStorageVersion(version)),
+            }
+            Ok(())
+        }
+
+        /// Returns the pending withdrawal, if any.
+        #[ink(message)]
+        pub fn pending_withdrawal(&self) -> Option<PendingWithdrawal> {
+            self.pending_withdrawal.clone()
+        }
+
+        /// Enables multisig control over privileged actions by `admins`, requiring
+        /// `threshold` confirmations per action. A `threshold` of `0` disables it.
+        ///
+        /// Only the contract owner can call this while multisig control is disabled;
+        /// afterwards admins have to propose `AdminAction::SetAdmins`.
+        #[ink(message)]
+        pub fn set_admins(&mut self, admins: Vec<AccountId>, threshold: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_multisig()?;
+            self.set_admins_impl(admins, threshold)
+        }
+
+        /// Replaces the admins and the number of confirmations an action needs.
+        fn set_admins_impl(&mut self, admins: Vec<AccountId>, threshold: u32) -> Result<()> {
+            if threshold as usize > admins.len() {
+                return Err(Error::InvalidThreshold)
+            }
+            self.admins = admins;
+            self.admin_threshold = threshold;
+            Ok(())
+        }
+
+        /// Returns the admins and the number of confirmations an action needs.
+        #[ink(message)]
+        pub fn admins(&self) -> (Vec<AccountId>, u32) {
+            (self.admins.clone(), self.admin_threshold)
+        }
+
+        /// Proposes `action` and confirms it on behalf of the calling admin.
+        ///
+        /// The action is executed as soon as it reaches the confirmation threshold.
+        /// Returns the id of the proposal.
+        #[ink(message)]
+        pub fn propose_action(&mut self, action: AdminAction) -> Result<u64> {
+            self.ensure_admin()?;
+            let id = self.next_proposal_id;
+            self.next_proposal_id += 1;
+            self.proposals.insert(
+                id,
+                Proposal {
+                    action,
+                    confirmations: Vec::new(),
+                    executed: false,
+                },
+            );
+            self.confirm_action(id)?;
+            Ok(id)
+        }
+
+        /// Confirms the proposal `id` on behalf of the calling admin.
+        ///
+        /// The action is executed as soon as it reaches the confirmation threshold.
+        /// Confirmations of admins that have been removed since do not count.
+        #[ink(message)]
+        pub fn confirm_action(&mut self, id: u64) -> Result<()> {
+            self.ensure_admin()?;
+            self.non_reentrant(|contract| contract.confirm_action_impl(id))
+        }
+
+        /// Confirms the proposal `id` on behalf of the calling admin, see `confirm_action`.
+        fn confirm_action_impl(&mut self, id: u64) -> Result<()> {
+            let caller = self.env().caller();
+            let mut proposal = self.proposals.get(&id).cloned().ok_or(Error::UnknownProposal)?;
+            if proposal.executed {
+                return Err(Error::ProposalExecuted)
+            }
+            if proposal.confirmations.contains(&caller) {
+                return Err(Error::AlreadyConfirmed)
+            }
+            // confirmations of admins removed since do not count
+            let admins = &self.admins;
+            proposal.confirmations.retain(|admin| admins.contains(admin));
+            proposal.confirmations.push(caller);
+            if proposal.confirmations.len() >= self.admin_threshold as usize {
+                self.execute_action(proposal.action.clone())?;
+                proposal.executed = true;
+            }
+            self.proposals.insert(id, proposal);
+            Ok(())
+        }
+
+        /// Returns the proposal `id`, if it exists.
+        #[ink(message)]
+        pub fn proposal(&self, id: u64) -> Option<Proposal> {
+            self.proposals.get(&id).cloned()
+        }
+ 
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with `value`.
+        ///
+        /// An `Approval` event is emitted.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.set_allowance(owner, spender, value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Returns the spenders `owner` has approved with their remaining allowances.
+        #[ink(message)]
+        pub fn approvals_of(&self, owner: AccountId) -> Vec<(AccountId, Balance)> {
+            self.approved_spenders
+                .get(&owner)
+                .map(|spenders| {
+                    spenders
+                        .iter()
+                        .map(|spender| (*spender, self.allowance_impl(&owner, spender)))
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// Revokes the allowances of all spenders approved by the caller.
+        ///
+        /// An `Approval` event with a zero value is emitted for every spender.
+        #[ink(message)]
+        pub fn revoke_all_approvals(&mut self) -> Result<()> {
+            let owner = self.env().caller();
+            let spenders = self.approved_spenders.take(&owner).unwrap_or_default();
+            for spender in spenders {
+                self.allowances.insert((&owner, &spender), &0);
+                self.env().emit_event(Approval {
+                    owner,
+                    spender,
+                    value: 0,
+                });
+            }
+            Ok(())
+        }
+
+        /// Sets the allowance of `spender` over the tokens of `owner` to `value`, keeping
+        /// track of the spenders `owner` has approved.
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.allowances.insert((&owner, &spender), &value);
+            let mut spenders = self.approved_spenders.get(&owner).cloned().unwrap_or_default();
+            let known = spenders.contains(&spender);
+            if value > 0 && !known {
+                spenders.push(spender);
+            } else if value == 0 && known {
+                spenders.retain(|approved| *approved != spender);
+            } else {
+                return
+            }
+            if spenders.is_empty() {
+                self.approved_spenders.take(&owner);
+            } else {
+                self.approved_spenders.insert(owner, spenders);
+            }
+        }
+
+        /// Sets the allowance of `spender` over the tokens of `owner` to `value`, authorized
+        /// by an off-chain ECDSA `signature` of `owner`.
+        ///
+        /// The signed message is the Blake2x256 hash of the SCALE encoded tuple
+        /// `(PERMIT_DOMAIN, contract, owner, spender, value, nonce, deadline)`, where `nonce`
+        /// is the current `nonce_of(owner)`. This lets a relayer approve on behalf of an
+        /// account that holds no native balance.
+        ///
+        /// An `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SignatureExpired` if `deadline` has passed and `InvalidSignature` if
+        /// the signature was not made by `owner`.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::SignatureExpired)
+            }
+            let payload = (
+                PERMIT_DOMAIN,
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                self.nonce_of(owner),
+                deadline,
+            );
+            self.use_signature(&owner, &payload, &signature)?;
+            self.set_allowance(owner, spender, value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Returns the nonce that the next payload signed by `account` must use.
+        #[ink(message)]
+        pub fn nonce_of(&self, account: AccountId) -> u64 {
+            *self.nonces.get(&account).unwrap_or(&0)
+        }
+
+        /// Returns the exact bytes `account` signs to prove it holds `seats`: the SCALE
+        /// encoded tuple `(TICKET_DOMAIN, contract, account, seats, nonce)`.
+        ///
+        /// `nonce` is a challenge chosen by the scanner, so a signature cannot be
+        /// replayed at another scan. The signature is made over the Blake2x256 hash of
+        /// the payload, see `ticket_payload_hash`, and checked by `verify_ticket`.
+        #[ink(message)]
+        pub fn ticket_payload(&self, account: AccountId, seats: Vec<String>, nonce: u64) -> Vec<u8> {
+            scale::Encode::encode(&(TICKET_DOMAIN, self.env().account_id(), account, seats, nonce))
+        }
+
+        /// Returns the Blake2x256 hash of `ticket_payload`, the message that is signed.
+        #[ink(message)]
+        pub fn ticket_payload_hash(&self, account: AccountId, seats: Vec<String>, nonce: u64) -> Hash {
+            let payload = self.ticket_payload(account, seats, nonce);
+            Hash::from(self.env().hash_bytes::<Blake2x256>(&payload))
+        }
+
+        /// Returns the hash identifying the ticket for `seat` held by `holder`, the
+        /// Blake2x256 hash of the SCALE encoded `(TICKET_HASH_DOMAIN, contract, seat,
+        /// holder)`.
+        ///
+        /// Door scanners can recompute it offline from a QR code holding the seat and
+        /// holder, and check it against chain state with `is_valid_ticket_hash`.
+        #[ink(message)]
+        pub fn ticket_hash(&self, seat: String, holder: AccountId) -> Hash {
+            let encoded = (TICKET_HASH_DOMAIN, self.env().account_id(), seat, holder);
+            Hash::from(self.env().hash_encoded::<Blake2x256, _>(&encoded))
+        }
+
+        /// Returns `true` if `hash` is the `ticket_hash` of `seat` and its current holder,
+        /// or its active delegate while the seat is lent out.
+        #[ink(message)]
+        pub fn is_valid_ticket_hash(&self, seat: String, hash: Hash) -> bool {
+            match self.seat_controller(&seat) {
+                Some(holder) => self.ticket_hash(seat, holder) == hash,
+                None => false,
+            }
+        }
+
+        /// Returns `true` if `signature` over the `ticket_payload` was made by `account`
+        /// and `account` holds, or is the active delegate of, all of `seats`.
+        #[ink(message)]
+        pub fn verify_ticket(&self, account: AccountId, seats: Vec<String>, nonce: u64, signature: [u8; 65]) -> bool {
+            if seats.iter().any(|seat| self.seat_controller(seat) != Some(account)) {
+                return false
+            }
+            let payload = self.ticket_payload(account, seats, nonce);
+            let message_hash = self.env().hash_bytes::<Blake2x256>(&payload);
+            self.recover_signer(&message_hash, &signature) == Ok(account)
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        ///
+        /// This can be used to allow a contract to transfer tokens on ones behalf and/or
+        /// to charge fees in sub-currencies, for example.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            self.ensure_transferable(&caller)?;
+            self.ensure_marketplace(&caller)?;
+            let allowance = self.allowance_impl(&from, &caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance)
+            }
+            self.transfer_from_to(&from, &to, value)?;
+            self.set_allowance(from, caller, allowance - value);
+            Ok(())
+        }
+
+
+        /// Burns `value` tickets of `from`, e.g. when they are redeemed at the door,
+        /// and emits a `Burned` event recording the `reason`.
+        ///
+        /// Only a verifier with the `CAN_BURN` scope can call this.
+        #[ink(message)]
+        pub fn burn(&mut self, from: AccountId, value: Balance, reason: BurnReason) -> Result<()> {
+            let caller = self.env().caller();
+            if !self.has_scope(caller, CAN_BURN) {
+                return Err(Error::NotVerifier)
+            }
+            let balance = self.balance_of_impl(&from);
+            if balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.set_balance(&from, balance - value);
+            self.redeemed_count += value;
+            self.env().emit_event(Burned { from, value, reason });
+            Ok(())
+        }
+
+
+        /// Appends a privileged `action` with its SCALE encoded `params` to the admin log.
+        fn log_admin_action(&mut self, action: &str, params: impl scale::Encode) {
+            let entry = AdminLogEntry {
+                caller: self.env().caller(),
+                timestamp: self.env().block_timestamp(),
+                action: String::from(action),
+                params: params.encode(),
+            };
+            self.admin_log.insert(self.admin_log_len, entry);
+            self.admin_log_len += 1;
+        }
+
+        /// Returns `NotOwner` if the caller is not the contract owner.
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            Ok(())
+        }
+
+        /// Runs `f` while holding the reentrancy guard.
+        ///
+        /// The storage struct is only written back once the message returns, so the
+        /// guard is written to contract storage directly, before `f` can call out, where
+        /// a reentrant call sees it. Guarded functions still update their state before
+        /// they transfer funds, usually as the last step through `pay_out`, since the
+        /// reentrant call would read the state from before the outer message.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ReentrantCall` if the guard is already held, i.e. when the contract
+        /// is called back while moving native funds or calling another contract.
+        fn non_reentrant<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            let guard = Key::from(REENTRANCY_GUARD_KEY);
+            if ink_env::get_contract_storage::<bool>(&guard).ok().flatten().unwrap_or(false) {
+                return Err(Error::ReentrantCall)
+            }
+            ink_env::set_contract_storage(&guard, &true);
+            let result = f(self);
+            ink_env::clear_contract_storage(&guard);
+            result
+        }
+
+        /// Returns `RefundMode` if the contract is in refund-only mode.
+        fn ensure_no_refund_mode(&self) -> Result<()> {
+            if self.refund_mode {
+                return Err(Error::RefundMode)
+            }
+            Ok(())
+        }
+
+        /// Returns `WithdrawalLocked` while withdrawals wait for the event to be held,
+        /// see `set_held_threshold`.
+        fn ensure_withdrawal_unlocked(&self) -> Result<()> {
+            if !self.is_event_held() {
+                return Err(Error::WithdrawalLocked)
+            }
+            Ok(())
+        }
+
+        /// Returns `MultisigRequired` if privileged actions are under multisig control.
+        fn ensure_no_multisig(&self) -> Result<()> {
+            if self.admin_threshold > 0 {
+                return Err(Error::MultisigRequired)
+            }
+            Ok(())
+        }
+
+        /// Returns `NotAdmin` if the caller is not an admin or multisig control is disabled.
+        fn ensure_admin(&self) -> Result<()> {
+            if self.admin_threshold == 0 || !self.admins.contains(&self.env().caller()) {
+                return Err(Error::NotAdmin)
+            }
+            Ok(())
+        }
+
+        /// Executes a privileged `action` confirmed by the admins.
+        fn execute_action(&mut self, action: AdminAction) -> Result<()> {
+            match action {
+                AdminAction::Clear => self.clear_impl()?,
+                AdminAction::FinalizeEvent { terminate } => {
+                    self.finalize_event_impl(terminate)?
+                }
+                AdminAction::RequestWithdraw(amount) => {
+                    self.request_withdraw_impl(amount)?
+                }
+                AdminAction::RequestTreasury(treasury) => {
+                    self.request_treasury_impl(treasury)?
+                }
+                AdminAction::RequestStream(stream) => {
+                    self.request_stream_impl(stream)?
+                }
+                AdminAction::SetWithdrawDelay(delay) => {
+                    self.set_withdraw_delay_impl(delay)?
+                }
+                AdminAction::AddVerifier(account) => {
+                    self.verifier.insert(account, true);
+                    self.verifier_expiry.take(&account);
+                    self.verifier_scopes.take(&account);
+                    self.log_admin_action("add_verifier", account);
+                }
+                AdminAction::SetTier { tier, price, seats } => {
+                    self.set_tier_impl(tier, price, seats)?
+                }
+                AdminAction::SetTierPrice { tier, price } => {
+                    self.set_tier_price_impl(tier, price)?
+                }
+                AdminAction::SetPricesBatch(prices) => {
+                    self.set_prices_batch_impl(prices)?
+                }
+                AdminAction::SetSeatPrice { seat, price } => {
+                    self.set_seat_price_impl(seat, price)?
+                }
+                AdminAction::SetZone { zone, capacity, price } => {
+                    self.set_zone_impl(zone, capacity, price)?
+                }
+                AdminAction::SetSku { sku, inventory, price } => {
+                    self.set_sku_impl(sku, inventory, price)?
+                }
+                AdminAction::SetGeneralAdmission { capacity, price } => {
+                    self.set_general_admission_impl(capacity, price)?
+                }
+                AdminAction::StartDutchAuction {
+                    seats,
+                    start_price,
+                    floor_price,
+                    decay_per_block,
+                } => self.start_dutch_auction_impl(seats, start_price, floor_price, decay_per_block)?,
+                AdminAction::EndDutchAuction => self.end_dutch_auction_impl(),
+                AdminAction::SetSurgePricing(pricing) => {
+                    self.set_surge_pricing_impl(pricing)?
+                }
+                AdminAction::SetCategoryPrice { category, price_bps } => {
+                    self.set_category_price_impl(category, price_bps)?
+                }
+                AdminAction::SetServiceFee { fee_bps, collector } => {
+                    self.set_service_fee_impl(fee_bps, collector)?
+                }
+                AdminAction::SetOracle { oracle, max_age } => {
+                    self.set_oracle_impl(oracle, max_age)
+                }
+                AdminAction::SetTips { accept, recipient } => {
+                    self.set_tips_impl(accept, recipient)?
+                }
+                AdminAction::SetRoyalty(royalty_bps) => self.set_royalty_impl(royalty_bps)?,
+                AdminAction::SetTransferFee(fee) => self.set_transfer_fee_impl(fee)?,
+                AdminAction::SetResaleFloor(floor) => self.set_resale_floor_impl(floor)?,
+                AdminAction::AddVerifierUntil { account, expiry } => {
+                    self.add_verifier_until_impl(account, expiry)?
+                }
+                AdminAction::SetVerifierScopes { account, scopes } => {
+                    self.set_verifier_scopes_impl(account, scopes)?
+                }
+                AdminAction::SetEmergencyPolicy { guardian, quorum } => {
+                    self.set_emergency_policy_impl(guardian, quorum)?
+                }
+                AdminAction::SetAdmins { admins, threshold } => {
+                    self.set_admins_impl(admins, threshold)?
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns `EventFinalized` once the event has been wound down, and
+        /// `NonTransferable` if tickets are soulbound and `caller` is not a verifier.
+        fn ensure_transferable(&self, caller: &AccountId) -> Result<()> {
+            if self.finalized {
+                return Err(Error::EventFinalized)
+            }
+            if self.soulbound && !self.is_verifier(*caller) {
+                return Err(Error::NonTransferable)
+            }
+            Ok(())
+        }
+
+        /// Returns `MarketplaceNotAllowed` if transfers are restricted and `caller` is not
+        /// an approved marketplace, and `EventStarted` if `caller` is a marketplace
+        /// reselling after the event start.
+        fn ensure_marketplace(&self, caller: &AccountId) -> Result<()> {
+            if self.restricted_transfers && !self.is_marketplace(*caller) {
+                return Err(Error::MarketplaceNotAllowed)
+            }
+            if self.is_marketplace(*caller) && self.has_event_started() {
+                return Err(Error::EventStarted)
+            }
+            Ok(())
+        }
+
+        /// Returns the account controlling `seat` at the gate: its active delegate, or
+        /// otherwise its holder.
+        fn seat_controller(&self, seat: &String) -> Option<AccountId> {
+            match self.seat_delegation(seat.clone()) {
+                Some(delegation) => Some(delegation.delegate),
+                None => self.seat_owner.get(seat).copied(),
+            }
+        }
+
+        /// Checks that `signature` over `payload` was made by `signer` and consumes the
+        /// current nonce of `signer`.
+        ///
+        /// The signer's account is derived from the recovered ECDSA public key as
+        /// the Blake2x256 hash of its compressed form.
+        fn use_signature<P: scale::Encode>(&mut self, signer: &AccountId, payload: &P, signature: &[u8; 65]) -> Result<()> {
+            let message_hash = self.env().hash_encoded::<Blake2x256, _>(payload);
+            if self.recover_signer(&message_hash, signature)? != *signer {
+                return Err(Error::InvalidSignature)
+            }
+            let nonce = self.nonce_of(*signer);
+            self.nonces.insert(*signer, nonce + 1);
+            Ok(())
+        }
+
+        /// Returns the account that made the ECDSA `signature` over `message_hash`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidSignature` if no public key can be recovered.
+        fn recover_signer(&self, message_hash: &[u8; 32], signature: &[u8; 65]) -> Result<AccountId> {
+            let mut public_key = [0u8; 33];
+            self.env()
+                .ecdsa_recover(signature, message_hash, &mut public_key)
+                .map_err(|_| Error::InvalidSignature)?;
+            Ok(AccountId::from(self.env().hash_bytes::<Blake2x256>(&public_key)))
+        }
+
+        /// Sells `value` tickets on `seats` to `to`, attributing the payment to `payer`.
+        fn purchase_impl(
+            &mut self,
+            payer: AccountId,
+            to: AccountId,
+            value: Balance,
+            signature: Vec<u8>,
+            seats: Vec<String>,
+            voucher: Option<String>,
+        ) -> Result<()> {
+            self.ensure_can_purchase(&payer)?;
+            if to != payer {
+                self.ensure_not_flagged(&to)?;
+            }
+            if self.has_seats && seats.is_empty() && value > 0 {
+                return self.purchase_general_admission(payer, to, value, signature, voucher)
+            }
+            if self.has_seats {
+                // a seat listed twice would be charged twice but sold once
+                Self::validate_seats(&seats, value)?;
+            }
+            let rate = self.native_rate()?;
+            let category = self.buyer_category(to);
+            let expected = self.concession_payment(value, &seats, rate, category);
+            let fee = self.service_fee_of(expected);
+            let (voucher, covered) = self.voucher_credit(voucher, expected)?;
+            let excess = self.check_payment(expected - covered + fee)?;
+            if self.has_seats {
+                if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                    return Err(Error::UnknownSeat(seat.clone()))
+                }
+                let unavailable: Vec<String> = seats
+                    .iter()
+                    .filter(|seat| !self.is_seat_free_for(Some(payer), seat))
+                    .cloned()
+                    .collect();
+                if !unavailable.is_empty() {
+                    return Err(Error::SeatsUnavailable(unavailable))
+                }
+                self.ensure_accessibility(&to, &seats)?;
+            }
+            let owner = self.owner;
+            if self.balance_of_impl(&owner) < value {
+                return Err(Error::SoldOut)
+            }
+            self.throttle(&payer, &to, value)?;
+
+            // add tokens to balance
+            self.transfer_from_to(&owner, &to, value)?;
+            let mut payouts = Vec::new();
+            if self.has_seats {
+                let purchased_at = self.env().block_timestamp();
+                // the voucher covers the first seats, so refunds only return what was paid
+                let mut credit = covered;
+                for seat in seats.iter() {
+                    let price = self.concession_price(self.seat_price(seat) * rate, category);
+                    let paid = price.saturating_sub(credit);
+                    credit -= price - paid;
+                    payouts.extend(self.take_hold(seat, None));
+                    self.set_seat_taken(seat, true);
+                    self.seat_owner.insert(seat.clone(), to);
+                    self.seat_purchases.insert(
+                        seat.clone(),
+                        SeatPurchase {
+                            payer,
+                            recipient: to,
+                            price: paid,
+                            purchased_at,
+                            category,
+                        },
+                    );
+                    self.env().emit_event(SeatSold {
+                        buyer: to,
+                        seat_id: self.seat_id(seat.clone()),
+                        seat: seat.clone(),
+                        price,
+                    });
+                }
+            }
+            self.record_revenue(value, &seats, rate, category);
+            self.proof_key.insert(to, signature);
+            self.redeem_voucher(voucher, &payer, covered);
+            self.record_payment(&payer, expected - covered);
+            self.collect_fee(fee);
+            if self.has_seats {
+                self.env().emit_event(SeatsPurchased {
+                    payer,
+                    recipient: to,
+                    seats,
+                    paid: expected,
+                });
+            } else {
+                self.env().emit_event(GeneralAdmissionPurchased {
+                    payer,
+                    recipient: to,
+                    quantity: value,
+                    paid: expected,
+                });
+            }
+            // prices adjust for the next purchase
+            self.record_surge_sales(value);
+            self.update_sold_out();
+            self.settle_excess(payer, excess)?;
+            self.pay_out(payouts)
+        }
+
+        /// Sells `value` general admission tickets of a seated contract to `to`, minting
+        /// them from the general admission allocation. They are priced like seats: at the
+        /// concession price of `to` and adjusted by demand-based pricing.
+        fn purchase_general_admission(
+            &mut self,
+            payer: AccountId,
+            to: AccountId,
+            value: Balance,
+            signature: Vec<u8>,
+            voucher: Option<String>,
+        ) -> Result<()> {
+            let remaining = self.ga_capacity - self.ga_sold;
+            if value > remaining as u128 {
+                return Err(Error::GeneralAdmissionSoldOut { remaining })
+            }
+            let rate = self.native_rate()?;
+            let expected = self.general_admission_payment(value, rate, self.buyer_category(to));
+            let fee = self.service_fee_of(expected);
+            let (voucher, covered) = self.voucher_credit(voucher, expected)?;
+            let excess = self.check_payment(expected - covered + fee)?;
+            if self.is_blacklisted(to) {
+                return Err(Error::Blacklisted)
+            }
+            self.throttle(&payer, &to, value)?;
+            self.ga_sold += value as u32;
+            let to_balance = self.balance_of_impl(&to);
+            self.set_balance(&to, to_balance + value);
+            Lazy::set(&mut self.total_supply, *self.total_supply + value);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+            let revenue = self.tier_revenue_of(DEFAULT_TIER) + expected;
+            self.tier_revenue.insert(String::from(DEFAULT_TIER), revenue);
+            self.proof_key.insert(to, signature);
+            self.redeem_voucher(voucher, &payer, covered);
+            self.record_payment(&payer, expected - covered);
+            self.collect_fee(fee);
+            self.env().emit_event(GeneralAdmissionPurchased {
+                payer,
+                recipient: to,
+                quantity: value,
+                paid: expected,
+            });
+            // prices adjust for the next purchase
+            self.record_surge_sales(value);
+            self.update_sold_out();
+            self.settle_excess(payer, excess)?;
+            Ok(())
+        }
+
+        /// Returns an error if `payer` is currently not allowed to purchase tickets.
+        fn ensure_can_purchase(&self, payer: &AccountId) -> Result<()> {
+            if self.finalized {
+                return Err(Error::EventFinalized)
+            }
+            self.ensure_no_refund_mode()?;
+            if self.has_event_started() {
+                return Err(Error::EventStarted)
+            }
+            if self.is_blacklisted(*payer) {
+                return Err(Error::Blacklisted)
+            }
+            if self.kyc_required && !self.is_kyc_attested(*payer) {
+                return Err(Error::KycRequired)
+            }
+            if let Some(extension) = self.identity_extension {
+                if !Self::has_identity(extension, payer) {
+                    return Err(Error::IdentityRequired)
+                }
+            }
+            self.ensure_not_flagged(payer)
+        }
+
+        /// Returns `AccessibilityRequired` with the first accessible seat of `seats` if
+        /// `recipient` has no accessibility eligibility attestation.
+        fn ensure_accessibility(&self, recipient: &AccountId, seats: &[String]) -> Result<()> {
+            if self.is_accessibility_eligible(*recipient) {
+                return Ok(())
+            }
+            match seats.iter().find(|seat| self.is_accessible_seat((*seat).clone())) {
+                Some(seat) => Err(Error::AccessibilityRequired(seat.clone())),
+                None => Ok(()),
+            }
+        }
+
+        /// Returns an error if the scalper registry flags `account`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `RegistryUnavailable` if the registry cannot be queried and
+        /// `FlaggedReseller` if it flags `account`.
+        fn ensure_not_flagged(&self, account: &AccountId) -> Result<()> {
+            let registry = match self.scalper_registry {
+                Some(registry) => registry,
+                None => return Ok(()),
+            };
+            let flagged = build_call::<DefaultEnvironment>()
+                .callee(registry)
+                .gas_limit(0)
+                .exec_input(ExecutionInput::new(Selector::new(IS_FLAGGED_SELECTOR)).push_arg(account))
+                .returns::<ReturnType<bool>>()
+                .fire()
+                .map_err(|_| Error::RegistryUnavailable)?;
+            if flagged {
+                return Err(Error::FlaggedReseller)
+            }
+            Ok(())
+        }
+
+        /// Counts `quantity` tickets against the purchase throttles of both `payer` and
+        /// `recipient`, so neither buying for many accounts nor receiving from many
+        /// payers gets around the limit.
+        ///
+        /// Must be called after all other checks of a purchase, as it records the
+        /// tickets.
+        ///
+        /// # Errors
+        ///
+        /// Returns `RateLimited` if `payer` or `recipient` would exceed the throttle
+        /// limit in the current block window.
+        fn throttle(&mut self, payer: &AccountId, recipient: &AccountId, quantity: Balance) -> Result<()> {
+            if self.throttle_limit == 0 {
+                return Ok(())
+            }
+            let window = self.env().block_number() / self.throttle_window;
+            let payer_bought = self.bought_in_window(payer, window);
+            let recipient_bought = self.bought_in_window(recipient, window);
+            if payer_bought.max(recipient_bought) as u128 + quantity > self.throttle_limit as u128 {
+                return Err(Error::RateLimited)
+            }
+            self.purchase_windows.insert(*payer, (window, payer_bought + quantity as u32));
+            if recipient != payer {
+                self.purchase_windows.insert(*recipient, (window, recipient_bought + quantity as u32));
+            }
+            Ok(())
+        }
+
+        /// Returns the tickets counted against the throttle of `account` in `window`.
+        fn bought_in_window(&self, account: &AccountId, window: BlockNumber) -> u32 {
+            match self.purchase_windows.get(account) {
+                Some((start, bought)) if *start == window => *bought,
+                _ => 0,
+            }
+        }
+
+        /// Asks the runtime chain extension function `extension` whether `account` has
+        /// an on-chain identity judgement.
+        fn has_identity(extension: u32, account: &AccountId) -> bool {
+            ChainExtensionMethod::build(extension)
+                .input::<AccountId>()
+                .output::<bool>()
+                .ignore_error_code()
+                .call(account)
+        }
+
+        /// Adds `amount` paid by `payer` to the contract balance.
+        fn record_payment(&mut self, payer: &AccountId, amount: Balance) {
+            self.contract_balance += amount;
+            self.refund_reserve += amount * self.reserve_bps as u128 / 10_000;
+            let paid = self.total_paid(*payer);
+            self.total_paid.insert(*payer, paid + amount);
+        }
+
+        /// Books a refund of `amount` to `payer`: it leaves the contract balance, no
+        /// longer counts as paid by `payer` and is released from the refund reserve.
+        fn record_refund(&mut self, payer: &AccountId, amount: Balance) {
+            self.contract_balance -= amount;
+            self.refund_reserve = self.refund_reserve.saturating_sub(amount);
+            let paid = self.total_paid(*payer);
+            self.total_paid.insert(*payer, paid.saturating_sub(amount));
+        }
+
+        /// Calls the `on_received` hook of the contract `to` and returns whether it
+        /// accepted `value` tokens sent by `operator` from `from`.
+        fn notify_received(&self, operator: &AccountId, from: &AccountId, to: &AccountId, value: Balance, data: Vec<u8>) -> bool {
+            let accepted = build_call::<DefaultEnvironment>()
+                .callee(*to)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_RECEIVED_SELECTOR))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<ReturnType<bool>>()
+                .fire();
+            matches!(accepted, Ok(true))
+        }
+
+        /// Makes a sold `seat` available again, returning its ticket from the holder to the
+        /// owner if the holder still has one.
+        fn release_seat(&mut self, seat: &String) {
+            if let Some(holder) = self.seat_owner.take(seat) {
+                self.reclaim_ticket(&holder);
+            }
+            self.seat_purchases.take(seat);
+            if self.checked_in.take(seat).is_some() {
+                self.checked_in_count -= 1;
+            }
+            for session_id in self.seat_sessions.take(seat).unwrap_or_default() {
+                let count = self.session_checked_in_count(session_id);
+                self.session_checked_in_count.insert(session_id, count.saturating_sub(1));
+            }
+            self.seat_sales.take(seat);
+            self.set_seat_taken(seat, false);
+            self.sold_out = false;
+        }
+
+        /// Returns a ticket of `holder`, if it still has one, to the owner. Unlike
+        /// `transfer_from_to` this ignores blacklisted and frozen accounts, so tickets
+        /// of released seats are always reclaimed.
+        fn reclaim_ticket(&mut self, holder: &AccountId) {
+            let balance = self.balance_of_impl(holder);
+            if balance == 0 {
+                return
+            }
+            let owner = self.owner;
+            self.set_balance(holder, balance - 1);
+            let owner_balance = self.balance_of_impl(&owner);
+            self.set_balance(&owner, owner_balance + 1);
+            self.env().emit_event(Transfer {
+                from: Some(*holder),
+                to: Some(owner),
+                value: 1,
+            });
+        }
+
+        /// Marks `seat` as sold or available, keeping the sold seat count up to date.
+        fn set_seat_taken(&mut self, seat: &String, taken: bool) {
+            let was_taken = *self.seat_taken.get(seat).unwrap_or(&false);
+            if taken && !was_taken {
+                self.seats_sold += 1;
+            } else if !taken && was_taken {
+                self.seats_sold -= 1;
+            }
+            self.seat_taken.insert(seat.clone(), taken);
+        }
+
+        /// Returns the service fee charged on top of a purchase `price`.
+        fn service_fee_of(&self, price: Balance) -> Balance {
+            price * self.fee_bps as u128 / 10_000
+        }
+
+        /// Sets aside a service `fee` for the fee collector.
+        fn collect_fee(&mut self, fee: Balance) {
+            self.fees_accrued += fee;
+            self.fee_collected += fee;
+        }
+
+        /// Returns the id of the `voucher` code, if any, and the part of the `price` it
+        /// covers.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownPromo` if the voucher does not exist or has been used up.
+        fn voucher_credit(&self, voucher: Option<String>, price: Balance) -> Result<(Option<Hash>, Balance)> {
+            let code = match voucher {
+                Some(code) => code,
+                None => return Ok((None, 0)),
+            };
+            let id = self.voucher_id(code.clone());
+            match self.vouchers.get(&id) {
+                Some(remaining) if *remaining > 0 => Ok((Some(id), price.min(*remaining))),
+                _ => Err(Error::UnknownPromo(code)),
+            }
+        }
+
+        /// Deducts `amount` from the value of voucher `id` redeemed by `payer`.
+        fn redeem_voucher(&mut self, id: Option<Hash>, payer: &AccountId, amount: Balance) {
+            let voucher = match id {
+                Some(voucher) => voucher,
+                None => return,
+            };
+            let remaining = self.voucher_balance(voucher) - amount;
+            self.vouchers.insert(voucher, remaining);
+            self.env().emit_event(VoucherRedeemed {
+                voucher,
+                payer: *payer,
+                amount,
+                remaining,
+            });
+        }
+
+        /// Checks the transferred value against the `expected` payment and returns the
+        /// part of it that is a tip or change, see `settle_excess`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `IncorrectPrice` if too little was sent, or too much while neither
+        /// tips are accepted nor change is returned.
+        fn check_payment(&self, expected: Balance) -> Result<Balance> {
+            let transferred = self.env().transferred_balance();
+            if transferred == expected {
+                return Ok(0)
+            }
+            if (self.accept_tips || self.return_change) && transferred > expected {
+                return Ok(transferred - expected)
+            }
+            Err(Error::IncorrectPrice {
+                expected,
+                provided: transferred,
+            })
+        }
+
+        /// Settles the `excess` sent along with a purchase paid for by `donor`: it is
+        /// forwarded to the tip recipient while tips are accepted, and returned to the
+        /// caller as change otherwise.
+        fn settle_excess(&mut self, donor: AccountId, excess: Balance) -> Result<()> {
+            if excess == 0 {
+                return Ok(())
+            }
+            if !self.accept_tips {
+                return self.env().transfer(self.env().caller(), excess).map_err(|_| Error::TransferFailed)
+            }
+            let recipient = match self.tip_recipient {
+                Some(recipient) => recipient,
+                None => return Ok(()),
+            };
+            self.total_donations += excess;
+            self.env().transfer(recipient, excess).map_err(|_| Error::TransferFailed)?;
+            self.env().emit_event(DonationReceived {
+                donor,
+                recipient,
+                amount: excess,
+            });
+            Ok(())
+        }
+
+        /// Records the sale of `seat` to `buyer` for `price` outside of a regular purchase,
+        /// accounting the payment to the seat's tier. The token has to be moved separately.
+        fn sell_seat(&mut self, seat: &String, buyer: AccountId, price: Balance) {
+            self.set_seat_taken(seat, true);
+            self.seat_owner.insert(seat.clone(), buyer);
+            self.seat_purchases.insert(
+                seat.clone(),
+                SeatPurchase {
+                    payer: buyer,
+                    recipient: buyer,
+                    price,
+                    purchased_at: self.env().block_timestamp(),
+                    category: BuyerCategory::Adult,
+                },
+            );
+            let tier = self
+                .seat_tier
+                .get(seat)
+                .cloned()
+                .unwrap_or_else(|| String::from(DEFAULT_TIER));
+            let revenue = self.tier_revenue_of(&tier) + price;
+            self.tier_revenue.insert(tier, revenue);
+            self.record_payment(&buyer, price);
+            self.env().emit_event(SeatSold {
+                buyer,
+                seat_id: self.seat_id(seat.clone()),
+                seat: seat.clone(),
+                price,
+            });
+            self.update_sold_out();
+        }
+
+        /// Emits `SoldOut` when the last ticket has just been sold, and rearms it once
+        /// tickets become available again.
+        fn update_sold_out(&mut self) {
+            let sold_out = self.is_sold_out();
+            if sold_out && !self.sold_out {
+                self.env().emit_event(SoldOut {
+                    timestamp: self.env().block_timestamp(),
+                });
+            }
+            self.sold_out = sold_out;
+        }
+
+        /// Returns the price of a single seat, taking a running Dutch auction, its price
+        /// override, its tier and demand-based pricing into account.
+        fn seat_price(&self, seat: &String) -> Balance {
+            if self.dutch_seats.contains_key(seat) {
+                if let Some(price) = self.dutch_price() {
+                    return price
+                }
+            }
+            self.surge_price(self.face_value(seat))
+        }
+
+        /// Returns the regular price of a single seat: its price override, or else the
+        /// price of its tier.
+        fn face_value(&self, seat: &String) -> Balance {
+            match (self.seat_price_override.get(seat), self.seat_tier.get(seat)) {
+                (Some(price), _) => *price,
+                (None, Some(tier)) => *self.tier_price.get(tier).unwrap_or(&self.price),
+                (None, None) => self.price,
+            }
+        }
+
+        /// Returns `price` adjusted by demand-based pricing.
+        fn surge_price(&self, price: Balance) -> Balance {
+            price * self.surge_bps() as u128 / 10_000
+        }
+
+        /// Counts `quantity` tickets sold towards demand-based pricing, raising prices by a
+        /// step for every `step_seats` tickets sold in the current window.
+        fn record_surge_sales(&mut self, quantity: Balance) {
+            let pricing = match self.surge_pricing.clone() {
+                Some(pricing) => pricing,
+                None => return,
+            };
+            let previous_bps = self.surge_bps;
+            let mut bps = self.surge_bps();
+            let window = self.env().block_number() / pricing.window;
+            if window != self.surge_window {
+                self.surge_window = window;
+                self.surge_sold = 0;
+            }
+            let sold_before = self.surge_sold;
+            self.surge_sold = sold_before.saturating_add(quantity as u32);
+            let steps = self.surge_sold / pricing.step_seats - sold_before / pricing.step_seats;
+            let increase = (pricing.step_bps as u64).saturating_mul(steps as u64);
+            bps = (bps as u64).saturating_add(increase).min(pricing.ceiling_bps as u64) as u16;
+            self.surge_bps = bps;
+            if bps != previous_bps {
+                self.env().emit_event(PriceAdjusted {
+                    previous_bps,
+                    current_bps: bps,
+                });
+            }
+        }
+
+        /// Returns `price` reduced to the concession price of `category`.
+        fn concession_price(&self, price: Balance, category: BuyerCategory) -> Balance {
+            price * self.category_price(category) as u128 / 10_000
+        }
+
+        /// Returns the amount that has to be paid for `value` tickets on `seats` by a buyer
+        /// of `category`, charging every seat at its concession price.
+        fn concession_payment(&self, value: Balance, seats: &[String], rate: Balance, category: BuyerCategory) -> Balance {
+            if self.has_seats {
+                seats
+                    .iter()
+                    .map(|seat| self.concession_price(self.seat_price(seat) * rate, category))
+                    .sum()
+            } else {
+                self.concession_price(self.required_payment(value, seats, rate), category)
+            }
+        }
+
+        /// Returns the amount that has to be paid for `value` general admission tickets by a
+        /// buyer of `category`.
+        fn general_admission_payment(&self, value: Balance, rate: Balance, category: BuyerCategory) -> Balance {
+            self.concession_price(self.surge_price(self.ga_price) * value * rate, category)
+        }
+
+        /// Returns the amount that has to be paid for `value` tickets on `seats`, converting
+        /// prices into native funds at `rate`.
+        fn required_payment(&self, value: Balance, seats: &[String], rate: Balance) -> Balance {
+            if self.has_seats {
+                seats.iter().map(|seat| self.seat_price(seat) * rate).sum()
+            } else {
+                self.surge_price(self.price) * value * rate
+            }
+        }
+
+        /// Returns the native amount per unit of price.
+        ///
+        /// This is `1` unless a price oracle is configured, in which case prices are
+        /// denominated in fiat minor units and the oracle's current rate is used.
+        ///
+        /// # Errors
+        ///
+        /// Returns `OracleUnavailable` if the oracle cannot be queried and
+        /// `StaleOraclePrice` if its rate is older than `oracle_max_age`.
+        fn native_rate(&self) -> Result<Balance> {
+            let oracle = match self.oracle {
+                Some(oracle) => oracle,
+                None => return Ok(1),
+            };
+            let (rate, updated_at) = build_call::<DefaultEnvironment>()
+                .callee(oracle)
+                .gas_limit(0)
+                .exec_input(ExecutionInput::new(Selector::new(ORACLE_RATE_SELECTOR)))
+                .returns::<ReturnType<(Balance, Timestamp)>>()
+                .fire()
+                .map_err(|_| Error::OracleUnavailable)?;
+            if self.env().block_timestamp().saturating_sub(updated_at) > self.oracle_max_age {
+                return Err(Error::StaleOraclePrice)
+            }
+            Ok(rate)
+        }
+
+        /// Returns the revenue accounted to `tier`.
+        fn tier_revenue_of(&self, tier: &str) -> Balance {
+            *self.tier_revenue.get(tier).unwrap_or(&0)
+        }
+
+        /// Accounts the revenue of a purchase of `value` tickets on `seats` for a buyer of
+        /// `category` to their tiers, converting prices into native funds at `rate`.
+        fn record_revenue(&mut self, value: Balance, seats: &[String], rate: Balance, category: BuyerCategory) {
+            if !self.has_seats {
+                let price = self.concession_price(self.surge_price(self.price) * value * rate, category);
+                let revenue = self.tier_revenue_of(DEFAULT_TIER) + price;
+                self.tier_revenue.insert(String::from(DEFAULT_TIER), revenue);
+                return
+            }
+            for seat in seats {
+                let tier = self
+                    .seat_tier
+                    .get(seat)
+                    .cloned()
+                    .unwrap_or_else(|| String::from(DEFAULT_TIER));
+                let price = self.concession_price(self.seat_price(seat) * rate, category);
+                let revenue = self.tier_revenue_of(&tier) + price;
+                self.tier_revenue.insert(tier, revenue);
+            }
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        fn transfer_from_to(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.move_balance(from, to, value)?;
+            self.env().emit_event(Transfer {
+                from: Some(*from),
+                to: Some(*to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Moves `value` tokens from `from` to `to` without emitting an event.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Blacklisted` error if either account is blacklisted.
+        ///
+        /// Returns `AccountFrozen` error if `from` is frozen and the caller is neither
+        /// the owner nor a verifier.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        ///
+        /// Returns `NonTransferable` error if the move would take tokens of zone tickets
+        /// `from` has not used yet.
+        fn move_balance(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            if self.is_blacklisted(*from) || self.is_blacklisted(*to) {
+                return Err(Error::Blacklisted)
+            }
+            let caller = self.env().caller();
+            if self.is_frozen(*from) && caller != self.owner && !self.is_verifier(caller) {
+                return Err(Error::AccountFrozen)
+            }
+            let from_balance = self.balance_of_impl(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            if from_balance - value < self.unused_zone_ticket_count(from) as Balance {
+                return Err(Error::NonTransferable)
+            }
+
+            self.set_balance(from, from_balance - value);
+            let to_balance = self.balance_of_impl(to);
+            self.set_balance(to, to_balance + value);
+            Ok(())
+        }
+    }
+
+    impl TicketSale for Erc20 {
+        /// Purchases `value` tickets for `to` and stores `signature` as proof of ownership.
+        ///
+        /// If the contract has seats, `seats` must hold exactly `value` available seats.
+        /// The transferred value must match the price of the tickets being bought. If tips
+        /// are accepted, anything above the price is forwarded to the tip recipient.
+        ///
+        /// A `voucher` code covers as much of the price as its remaining value allows,
+        /// only the rest has to be transferred. The service fee is not covered.
+        ///
+        /// Accessible seats can only be bought for a `to` with an accessibility
+        /// eligibility attestation, see `set_accessible_seats`. Tickets are charged at
+        /// the price of the concession category attested for `to`, which is recorded in
+        /// the seat's purchase record.
+        #[ink(message, payable)]
+        fn purchase_tickets(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            signature: Vec<u8>,
+            seats: Vec<String>,
+            voucher: Option<String>,
+        ) -> Result<()> {
+            let payer = self.env().caller();
+            self.non_reentrant(|contract| contract.purchase_impl(payer, to, value, signature, seats, voucher))
+        }
+
+        /// Returns the exact native amount to transfer with `purchase_tickets` for `seats`
+        /// bought for `to`, accounting for tiers, per-seat prices, a running Dutch
+        /// auction, the oracle rate, the concession category of `to` and the service fee.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoSeats` for contracts without seats, `UnknownSeat` if a seat is not
+        /// part of this event and `UnknownPromo` if the voucher code `promo` does not
+        /// exist or has been used up.
+        #[ink(message)]
+        fn quote(&self, to: AccountId, seats: Vec<String>, promo: Option<String>) -> Result<Balance> {
+            if !self.has_seats {
+                return Err(Error::NoSeats)
+            }
+            if let Some(seat) = seats.iter().find(|seat| !self.seat_taken.contains_key(*seat)) {
+                return Err(Error::UnknownSeat(seat.clone()))
+            }
+            let rate = self.native_rate()?;
+            let category = self.buyer_category(to);
+            let price = self.concession_payment(seats.len() as u128, &seats, rate, category);
+            let (_, covered) = self.voucher_credit(promo, price)?;
+            Ok(price - covered + self.service_fee_of(price))
+        }
+
+        /// Returns `true` if all `seats` are available for purchase.
+        #[ink(message)]
+        fn is_seat_available(&self, seats: Vec<String>) -> bool {
+            seats.into_iter().all(|seat| self.is_seat_free(seat))
+        }
+
+        /// Returns `true` if `seat` is part of this event, has not been sold and is not
+        /// blocked or held.
+        #[ink(message)]
+        fn is_seat_free(&self, seat: String) -> bool {
+            self.is_seat_free_for(None, &seat)
+        }
+
+        /// Returns the sale state of `seat`, or `None` if it is not part of this event.
+        #[ink(message)]
+        fn seat_status(&self, seat: String) -> Option<SeatStatus> {
+            let taken = *self.seat_taken.get(&seat)?;
+            if self.is_seat_blocked(seat.clone()) {
+                return Some(SeatStatus::Blocked)
+            }
+            if self.checked_in.contains_key(&seat) {
+                return Some(SeatStatus::CheckedIn)
+            }
+            if taken {
+                let owner = *self.seat_owner.get(&seat).unwrap_or(&self.owner);
+                return Some(SeatStatus::Sold { owner })
+            }
+            let until = if let Some(hold) = self.active_hold(&seat) {
+                Some(hold.until)
+            } else if let Some(invoice) = self.active_invoice(&seat) {
+                Some(invoice.deadline)
+            } else if let Some(plan) = self.active_installment_plan(&seat) {
+                Some(plan.next_due)
+            } else if let Some(auction) = self.auctions.get(&seat) {
+                Some(auction.ends_at)
+            } else if self.lottery_seats.contains_key(&seat) {
+                self.lottery.as_ref().map(|lottery| lottery.entry_end)
+            } else {
+                None
+            };
+            match until {
+                Some(until) => Some(SeatStatus::Held { until }),
+                None => Some(SeatStatus::Available),
+            }
+        }
+
+        /// Returns the current holder of `seat`, if it has been sold.
+        #[ink(message)]
+        fn seat_owner(&self, seat: String) -> Option<AccountId> {
+            self.seat_owner.get(&seat).copied()
+        }
+
+        /// Checks in the holder of `seat` at the venue, or its active delegate while the
+        /// seat is lent out with `delegate_seat`.
+        ///
+        /// A ticket grants entry to each of the `session_count` sessions once, so
+        /// `session_id` must be below it.
+        ///
+        /// Only a verifier with the `CAN_CHECK_IN` scope can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `UnknownSession` if `session_id` is not part of the event,
+        /// `SeatNotSold` if nobody holds `seat`, `AlreadyCheckedIn` if its holder has been
+        /// checked in to the session before and `CheckInClosed` once the grace period
+        /// after the event start has passed.
+        #[ink(message)]
+        fn check_in(&mut self, seat: String, session_id: u32) -> Result<()> {
+            let verifier = self.env().caller();
+            if !self.has_scope(verifier, CAN_CHECK_IN) {
+                return Err(Error::NotVerifier)
+            }
+            if let Some(start) = self.event_start {
+                if self.env().block_timestamp() > start.saturating_add(self.check_in_grace) {
+                    return Err(Error::CheckInClosed)
+                }
+            }
+            if session_id >= self.session_count() {
+                return Err(Error::UnknownSession(session_id))
+            }
+            let holder = self
+                .seat_controller(&seat)
+                .ok_or_else(|| Error::SeatNotSold(seat.clone()))?;
+            let mut sessions = self.checked_in_sessions(seat.clone());
+            if sessions.contains(&session_id) {
+                return Err(Error::AlreadyCheckedIn)
+            }
+            sessions.push(session_id);
+            self.seat_sessions.insert(seat.clone(), sessions);
+            let count = self.session_checked_in_count(session_id);
+            self.session_checked_in_count.insert(session_id, count + 1);
+            if self.checked_in.insert(seat.clone(), true).is_none() {
+                self.checked_in_count += 1;
+            }
+            self.env().emit_event(SeatCheckedIn {
+                holder,
+                verifier,
+                seat_id: self.seat_id(seat.clone()),
+                seat,
+                session_id,
+            });
+            Ok(())
+        }
+
+        /// Cancels the ticket for `seat` held by `holder`, e.g. after fraud or a sale
+        /// made in error. The recorded purchase price is refunded to the payer from the
+        /// contract balance and the seat becomes available again.
+        ///
+        /// Only the contract owner or a verifier with the `CAN_REFUND` scope can call this.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSeatOwner` if `holder` does not hold `seat`, `DisputeOpen` if the
+        /// seat is disputed and `InsufficientBalance` if the refund is not covered by the
+        /// withdrawable balance.
+        #[ink(message)]
+        fn cancel_ticket(&mut self, holder: AccountId, seat: String, reason: CancelReason) -> Result<()> {
+            let by = self.env().caller();
+            if by != self.owner && !self.has_scope(by, CAN_REFUND) {
+                return Err(Error::NotVerifier)
+            }
+            if self.seat_owner.get(&seat) != Some(&holder) {
+                return Err(Error::NotSeatOwner)
+            }
+            if self.disputes.contains_key(&seat) {
+                return Err(Error::DisputeOpen)
+            }
+            let purchase = self.seat_purchases.get(&seat).cloned();
+            let refund = purchase.as_ref().map_or(0, |purchase| purchase.price);
+            if refund > self.withdrawable_balance() {
+                return Err(Error::InsufficientBalance)
+            }
+            self.non_reentrant(|contract| {
+                contract.release_seat(&seat);
+                if let Some(purchase) = purchase {
+                    contract.record_refund(&purchase.payer, refund);
+                    contract.env().transfer(purchase.payer, refund).map_err(|_| Error::TransferFailed)?;
+                }
+                contract.env().emit_event(TicketCancelled {
+                    holder,
+                    by,
+                    seat,
+                    refund,
+                    reason,
+                });
+                Ok(())
+            })
+        }
+    }
+
+    /// Ticket-holder governance: the organizer opens proposals that holders vote on,
+    /// weighted by the tickets they held when the proposal was opened.
+    impl Erc20 {
+        /// Opens a proposal on `question` that ticket holders can vote on until
+        /// `deadline`, and returns its id.
+        ///
+        /// Voting weights are taken from a balance snapshot made when the proposal is
+        /// opened, so tickets cannot be moved around to vote twice. Only the contract
+        /// owner can call this.
+        #[ink(message)]
+        pub fn open_proposal(&mut self, question: String, deadline: Timestamp) -> Result<u64> {
+            self.ensure_owner()?;
+            if deadline <= self.env().block_timestamp() {
+                return Err(Error::InvalidDeadline)
+            }
+            let snapshot_id = self.snapshot()?;
+            let id = self.next_governance_id;
+            self.next_governance_id += 1;
+            self.governance_proposals.insert(
+                id,
+                GovernanceProposal {
+                    question,
+                    deadline,
+                    snapshot_id,
+                    yes: 0,
+                    no: 0,
+                    closed: false,
+                },
+            );
+            Ok(id)
+        }
+
+        /// Returns the governance proposal `id`, if it exists.
+        #[ink(message)]
+        pub fn governance_proposal(&self, id: u64) -> Option<GovernanceProposal> {
+            self.governance_proposals.get(&id).cloned()
+        }
+
+        /// Returns `true` if `voter` has voted on the governance proposal `id`.
+        #[ink(message)]
+        pub fn has_voted(&self, id: u64, voter: AccountId) -> bool {
+            self.votes.contains_key(&(id, voter))
+        }
+
+        /// Votes for or against the governance proposal `id` with the tickets the
+        /// caller held when it was opened.
+        ///
+        /// # Errors
+        ///
+        /// Returns `VotingClosed` after the deadline, `AlreadyVoted` on a second vote
+        /// and `InsufficientBalance` if the caller held no tickets.
+        #[ink(message)]
+        pub fn vote(&mut self, id: u64, support: bool) -> Result<()> {
+            let voter = self.env().caller();
+            let mut proposal = self
+                .governance_proposals
+                .get(&id)
+                .cloned()
+                .ok_or(Error::UnknownProposal)?;
+            if proposal.closed || self.env().block_timestamp() > proposal.deadline {
+                return Err(Error::VotingClosed)
+            }
+            if self.votes.contains_key(&(id, voter)) {
+                return Err(Error::AlreadyVoted)
+            }
+            let weight = self.balance_of_at(voter, proposal.snapshot_id)?;
+            if weight == 0 {
+                return Err(Error::InsufficientBalance)
+            }
+            if support {
+                proposal.yes += weight;
+            } else {
+                proposal.no += weight;
+            }
+            self.governance_proposals.insert(id, proposal);
+            self.votes.insert((id, voter), support);
+            self.env().emit_event(VoteCast {
+                voter,
+                id,
+                support,
+                weight,
+            });
+            Ok(())
+        }
+
+        /// Closes the governance proposal `id` once its deadline has passed and emits
+        /// the final tally.
+        ///
+        /// Anyone can call this.
+        #[ink(message)]
+        pub fn close_proposal(&mut self, id: u64) -> Result<()> {
+            let mut proposal = self
+                .governance_proposals
+                .get(&id)
+                .cloned()
+                .ok_or(Error::UnknownProposal)?;
+            if proposal.closed {
+                return Err(Error::VotingClosed)
+            }
+            if self.env().block_timestamp() <= proposal.deadline {
+                return Err(Error::VotingOpen)
+            }
+            proposal.closed = true;
+            let (yes, no) = (proposal.yes, proposal.no);
+            self.governance_proposals.insert(id, proposal);
+            self.env().emit_event(ProposalClosed {
+                id,
+                yes,
+                no,
+                passed: yes > no,
+            });
+            Ok(())
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(not(feature = "ink-experimental-engine"))]
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        type Event = <Erc20 as ::ink_lang::reflect::ContractEventBase>::Type;
+
+        use ink_lang as ink;
+
+        fn assert_transfer_event(
+            event: &ink_env::test::EmittedEvent,
+            expected_from: Option<AccountId>,
+            expected_to: Option<AccountId>,
+            expected_value: Balance,
+        ) {
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Transfer(Transfer { from, to, value }) = decoded_event {
+                assert_eq!(from, expected_from, "encountered invalid Transfer.from");
+                assert_eq!(to, expected_to, "encountered invalid Transfer.to");
+                assert_eq!(value, expected_value, "encountered invalid Trasfer.value");
+            } else {
+                panic!("encountered unexpected event kind: expected a Transfer event")
+            }
+            let expected_topics = vec![
+                encoded_into_hash(&PrefixedValue {
+                    value: b"Erc20::Transfer",
+                    prefix: b"",
+                }),
+                encoded_into_hash(&PrefixedValue {
+                    prefix: b"Erc20::Transfer::from",
+                    value: &expected_from,
+                }),
+                encoded_into_hash(&PrefixedValue {
+                    prefix: b"Erc20::Transfer::to",
+                    value: &expected_to,
+                }),
+            ];
+            // `value` is event data, only `from` and `to` are topics.
+            assert_eq!(event.topics.len(), expected_topics.len(), "encountered invalid topic count");
+            for (n, (actual_topic, expected_topic)) in
+                event.topics.iter().zip(expected_topics).enumerate()
+            {
+                let topic = actual_topic
+                    .decode::<Hash>()
+                    .expect("encountered invalid topic encoding");
+                assert_eq!(topic, expected_topic, "encountered invalid topic at {}", n);
+            }
+        }
+
+        fn assert_deployed_event(
+            event: &ink_env::test::EmittedEvent,
+            expected_deployer: AccountId,
+            expected_owner: AccountId,
+        ) {
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Deployed(Deployed { deployer, owner }) = decoded_event {
+                assert_eq!(deployer, expected_deployer, "encountered invalid Deployed.deployer");
+                assert_eq!(owner, expected_owner, "encountered invalid Deployed.owner");
+            } else {
+                panic!("encountered unexpected event kind: expected a Deployed event")
+            }
+        }
+
+        /// The default constructor does its job.
+        #[ink::test]
+        fn new_works() {
+            // Constructor works.
+            let _erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
+
+            // Transfer and Deployed events triggered during initial construction.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(2, emitted_events.len());
+
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            assert_deployed_event(
+                &emitted_events[1],
+                AccountId::from([0x01; 32]),
+                AccountId::from([0x01; 32]),
+            );
+        }
+
+        /// The total supply was applied.
+        #[ink::test]
+        fn total_supply_works() {
+            // Constructor works.
+            let erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
+            // Transfer event triggered during initial construction.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            // Get the token total supply.
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        /// Get the actual balance of an account.
+        #[ink::test]
+        fn balance_of_works() {
+            // Constructor works
+            let erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
+            // Transfer event triggered during initial construction
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Alice owns all the tokens on contract instantiation
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            // Bob does not owns tokens
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            // Constructor works.
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
+            // Transfer event triggered during initial construction.
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            // Alice transfers 10 tokens to Bob.
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            // Bob owns 10 tokens.
+            assert_eq!(erc20.balance_of(accounts.bob), 10);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 3);
+            // Check first transfer event related to ERC-20 instantiation.
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            // The second event `emitted_events[1]` is the Deployed event.
+            // Check the second transfer event relating to the actual trasfer.
+            assert_transfer_event(
+                &emitted_events[2],
+                Some(AccountId::from([0x01; 32])),
+                Some(AccountId::from([0x02; 32])),
+                10,
+            );
+        }
+
+        #[ink::test]
+        fn invalid_transfer_should_fail() {
+            // Constructor works.
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            // Create call
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+
+            // Bob fails to transfers 10 tokens to Eve.
+            assert_eq!(
+                erc20.transfer(accounts.eve, 10),
+                Err(Error::InsufficientBalance)
+            );
+            // Alice owns all the tokens.
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.balance_of(accounts.eve), 0);
+
+            // Transfer and Deployed events triggered during initial construction.
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+        }
+
+        #[ink::test]
+        fn transfer_from_works() {
+            // Constructor works.
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
+            // Transfer event triggered during initial construction.
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Bob fails to transfer tokens owned by Alice.
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.eve, 10),
+                Err(Error::InsufficientAllowance)
+            );
+            // Alice approves Bob for token transfers on her behalf.
+            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+
+            // The approve event takes place.
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            // Create call.
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller.
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+
+            // Bob transfers tokens from Alice to Eve.
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.eve, 10),
+                Ok(())
+            );
+            // Eve owns tokens.
+            assert_eq!(erc20.balance_of(accounts.eve), 10);
+
+            // Check all transfer events that happened during the previous calls:
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_event(
+                &emitted_events[0],
+                None,
+                Some(AccountId::from([0x01; 32])),
+                100,
+            );
+            // `emitted_events[1]` is the Deployed event and `emitted_events[2]` an Approve
+            // event that we skip checking.
+            assert_transfer_event(
+                &emitted_events[3],
+                Some(AccountId::from([0x01; 32])),
+                Some(AccountId::from([0x05; 32])),
+                10,
+            );
+        }
+
+        #[ink::test]
+        fn allowance_must_not_change_on_failed_transfer() {
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Alice approves Bob for token transfers on her behalf.
+            let alice_balance = erc20.balance_of(accounts.alice);
+            let initial_allowance = alice_balance + 2;
+            assert_eq!(erc20.approve(accounts.bob, initial_allowance), Ok(()));
+
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            // Create call.
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller.
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+
+            // Bob tries to transfer tokens from Alice to Eve.
+            let emitted_events_before = ink_env::test::recorded_events();
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.eve, alice_balance + 1),
+                Err(Error::InsufficientBalance)
+            );
+            // Allowance must have stayed the same
+            assert_eq!(
+                erc20.allowance(accounts.alice, accounts.bob),
+                initial_allowance
+            );
+            // No more events must have been emitted
+            let emitted_events_after = ink_env::test::recorded_events();
+            assert_eq!(emitted_events_before.count(), emitted_events_after.count());
+        }
+
+        /// Creates a seated contract owned by Alice with seats `A1`, `A2` and `A3` at a
+        /// price of 10 per ticket.
+        fn seated_erc20() -> Erc20 {
+            let seats = ["A1", "A2", "A3"].iter().map(|seat| String::from(*seat)).collect();
+            Erc20::new(3, 10, AccountId::from([0x01; 32]), seats)
+        }
+
+        /// Makes `caller` the sender of the next calls, transferring `value` with them.
+        fn set_sender(caller: AccountId, value: Balance) {
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                caller,
+                callee,
+                1000000,
+                value,
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])),
+            );
+        }
+
+        fn seats(labels: &[&str]) -> Vec<String> {
+            labels.iter().map(|seat| String::from(*seat)).collect()
+        }
+
+        #[ink::test]
+        fn purchase_tickets_works() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            // Bob buys two seats for himself.
+            set_sender(accounts.bob, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 2);
+            assert_eq!(erc20.balance_of(accounts.alice), 1);
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.bob));
+            assert!(!erc20.is_seat_free(String::from("A2")));
+            assert!(erc20.is_seat_free(String::from("A3")));
+            assert_eq!(erc20.total_paid(accounts.bob), 20);
+            assert_eq!(erc20.withdrawable_balance(), 20);
+        }
+
+        #[ink::test]
+        fn purchase_with_incorrect_price_fails() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_sender(accounts.bob, 15);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Err(Error::IncorrectPrice {
+                    expected: 20,
+                    provided: 15,
+                })
+            );
+            // Nothing was sold.
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert!(erc20.is_seat_free(String::from("A1")));
+            assert_eq!(erc20.withdrawable_balance(), 0);
+        }
+
+        #[ink::test]
+        fn voucher_covers_part_of_price() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let voucher = erc20.voucher_id(String::from("GIFT"));
+
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.mint_voucher(voucher, 15), Err(Error::NotOwner));
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.mint_voucher(voucher, 15), Ok(()));
+            assert_eq!(
+                erc20.quote(accounts.bob, seats(&["A1", "A2"]), Some(String::from("GIFT"))),
+                Ok(5)
+            );
+
+            // Bob only pays what the voucher does not cover.
+            set_sender(accounts.bob, 5);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), Some(String::from("GIFT"))),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 2);
+            assert_eq!(erc20.voucher_balance(voucher), 0);
+            assert_eq!(erc20.total_paid(accounts.bob), 5);
+            assert_eq!(erc20.withdrawable_balance(), 5);
+
+            // A used up voucher is rejected.
+            set_sender(accounts.charlie, 0);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 1, vec![1], seats(&["A3"]), Some(String::from("GIFT"))),
+                Err(Error::UnknownPromo(String::from("GIFT")))
+            );
+        }
+
+        #[ink::test]
+        fn purchase_of_taken_seat_fails() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            // Charlie cannot buy the seat Bob already holds.
+            set_sender(accounts.charlie, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 2, vec![1], seats(&["A1", "A3"]), None),
+                Err(Error::SeatsUnavailable(seats(&["A1"])))
+            );
+            // Seats have to match the amount of tickets.
+            set_sender(accounts.charlie, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 2, vec![1], seats(&["A3"]), None),
+                Err(Error::SeatMismatch {
+                    seats: 1,
+                    tickets: 2,
+                })
+            );
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 1, vec![1], seats(&["B1"]), None),
+                Err(Error::UnknownSeat(String::from("B1")))
+            );
+            // A seat cannot be bought twice in one purchase.
+            set_sender(accounts.charlie, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 2, vec![1], seats(&["A3", "A3"]), None),
+                Err(Error::DuplicateSeat(String::from("A3")))
+            );
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn cancel_ticket_refunds_buyer() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
+            let bob_funds =
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.bob)
+                    .expect("Cannot get account balance");
+
+            // Only the owner or a verifier can cancel.
+            set_sender(accounts.charlie, 0);
+            assert_eq!(
+                erc20.cancel_ticket(accounts.bob, String::from("A1"), CancelReason::Fraud),
+                Err(Error::NotVerifier)
+            );
+            set_sender(accounts.alice, 0);
+            assert_eq!(
+                erc20.cancel_ticket(accounts.charlie, String::from("A1"), CancelReason::Fraud),
+                Err(Error::NotSeatOwner)
+            );
+            assert_eq!(
+                erc20.cancel_ticket(accounts.bob, String::from("A2"), CancelReason::Fraud),
+                Err(Error::NotSeatOwner)
+            );
+            assert_eq!(
+                erc20.cancel_ticket(accounts.bob, String::from("A1"), CancelReason::Fraud),
+                Ok(())
+            );
+            assert_eq!(
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.bob),
+                Ok(bob_funds + 10)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.total_paid(accounts.bob), 0);
+            assert!(erc20.is_seat_free(String::from("A1")));
+            assert_eq!(erc20.withdrawable_balance(), 0);
+        }
+
+        #[ink::test]
+        fn reverse_purchase_within_window() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_reversal_window(1_000), Ok(()));
+            set_sender(accounts.bob, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 20)
+                .expect("Cannot set account balance");
+
+            set_sender(accounts.charlie, 0);
+            assert_eq!(
+                erc20.reverse_purchase(seats(&["A1"]), CancelReason::Fraud),
+                Err(Error::NotVerifier)
+            );
+            set_sender(accounts.alice, 0);
+            assert_eq!(
+                erc20.reverse_purchase(seats(&["A1", "A3"]), CancelReason::Fraud),
+                Err(Error::SeatNotSold(String::from("A3")))
+            );
+            assert_eq!(
+                erc20.reverse_purchase(seats(&["A1", "A2"]), CancelReason::Fraud),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.balance_of(accounts.alice), 3);
+            assert_eq!(erc20.total_paid(accounts.bob), 0);
+            assert!(erc20.is_seat_free(String::from("A1")));
+            assert_eq!(erc20.withdrawable_balance(), 0);
+        }
+
+        #[ink::test]
+        fn clear_withdraws_to_owner() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.bob, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 20)
+                .expect("Cannot set account balance");
+            let alice_funds =
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.alice)
+                    .expect("Cannot get account balance");
+
+            // Only the owner can withdraw.
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.clear(), Err(Error::NotOwner));
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.clear(), Ok(()));
+            assert_eq!(
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.alice),
+                Ok(alice_funds + 20)
+            );
+            assert_eq!(erc20.withdrawable_balance(), 0);
+        }
+
+        #[ink::test]
+        fn withdrawals_go_to_treasury() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            // Treasury changes are always timelocked.
+            assert_eq!(erc20.request_treasury(Some(accounts.eve)), Err(Error::TimelockRequired));
+            assert_eq!(erc20.set_withdraw_delay(1), Ok(()));
+            assert_eq!(erc20.request_treasury(Some(accounts.eve)), Ok(()));
+            assert_eq!(erc20.request_treasury(None), Err(Error::TreasuryChangePending));
+            assert_eq!(erc20.execute_treasury_change(), Err(Error::TimelockNotExpired));
+            assert_eq!(erc20.withdrawal_destination(), accounts.alice);
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(erc20.execute_treasury_change(), Ok(()));
+            assert_eq!(erc20.treasury(), Some(accounts.eve));
+            assert_eq!(erc20.execute_treasury_change(), Err(Error::NoPendingTreasuryChange));
+
+            set_sender(accounts.bob, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 20)
+                .expect("Cannot set account balance");
+            let treasury_funds =
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.eve)
+                    .expect("Cannot get account balance");
+
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.clear(), Err(Error::WithdrawalTimelocked));
+            assert_eq!(erc20.request_withdraw(20), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(erc20.execute_withdraw(), Ok(()));
+            assert_eq!(
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.eve),
+                Ok(treasury_funds + 20)
+            );
+        }
+
+        #[ink::test]
+        fn frozen_accounts_cannot_transfer() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.freeze(accounts.bob), Err(Error::NotVerifier));
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            assert!(erc20.is_frozen(accounts.bob));
+
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.transfer(accounts.charlie, 1), Err(Error::AccountFrozen));
+            assert_eq!(erc20.balance_of(accounts.bob), 1);
+
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.unfreeze(accounts.bob), Ok(()));
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.transfer(accounts.charlie, 1), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.charlie), 1);
+        }
+
+        #[ink::test]
+        fn verifier_quorum_switches_to_refund_mode() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_emergency_policy(None, 2), Ok(()));
+            assert_eq!(erc20.add_verifier(accounts.charlie), Ok(()));
+            assert_eq!(erc20.add_verifier(accounts.django), Ok(()));
+
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.emergency_refund(), Err(Error::NotGuardian));
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.emergency_refund(), Ok(()));
+            assert_eq!(erc20.emergency_refund(), Err(Error::AlreadySignalled));
+            assert!(!erc20.is_refund_mode());
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.emergency_refund(), Ok(()));
+            assert!(erc20.is_refund_mode());
+
+            // Purchases and owner withdrawals stop.
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.clear(), Err(Error::RefundMode));
+            assert_eq!(erc20.releasable_balance(), 0);
+            set_sender(accounts.charlie, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 1, vec![1], seats(&["A2"]), None),
+                Err(Error::RefundMode)
+            );
+
+            // Bob reclaims his payment, once.
+            let bob_funds =
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.bob)
+                    .expect("Cannot get account balance");
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.claim_emergency_refund(), Ok(()));
+            assert_eq!(
+                ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(accounts.bob),
+                Ok(bob_funds + 10)
+            );
+            assert_eq!(erc20.claim_emergency_refund(), Err(Error::NothingToRefund));
+        }
+
+        #[ink::test]
+        fn withdrawals_wait_for_event_held() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_held_threshold(1), Ok(()));
+            assert_eq!(erc20.set_held_threshold(0), Err(Error::InvalidThreshold));
+            assert_eq!(erc20.add_verifier(accounts.charlie), Ok(()));
+            assert_eq!(erc20.clear(), Err(Error::WithdrawalLocked));
+
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.attest_event_held(), Err(Error::NotVerifier));
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.attest_event_held(), Ok(()));
+            assert_eq!(erc20.attest_event_held(), Err(Error::AlreadyAttested));
+            assert!(erc20.is_event_held());
+
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.clear(), Ok(()));
+            assert_eq!(erc20.withdrawable_balance(), 0);
+        }
+
+        #[ink::test]
+        fn refund_reserve_is_held_back() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_refund_reserve(1_000, 1_000), Ok(()));
+            assert_eq!(erc20.set_refund_reserve(500, 1_000), Err(Error::InvalidReserve));
+            set_sender(accounts.bob, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 20)
+                .expect("Cannot set account balance");
+            assert_eq!(erc20.locked_reserve(), 2);
+            assert_eq!(erc20.releasable_balance(), 18);
+
+            // The owner can only take out what is not reserved.
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.clear(), Ok(()));
+            assert_eq!(erc20.withdrawable_balance(), 2);
+        }
+
+        #[ink::test]
+        fn admin_actions_are_logged() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.add_verifier(accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_seat_price(String::from("A1"), Some(5)), Ok(()));
+            // Failed actions are not logged.
+            assert_eq!(
+                erc20.set_seat_price(String::from("B1"), Some(5)),
+                Err(Error::UnknownSeat(String::from("B1")))
+            );
+
+            assert_eq!(erc20.admin_log_len(), 2);
+            let log = erc20.admin_log(1, 10);
+            assert_eq!(log.len(), 1);
+            assert_eq!(log[0].caller, accounts.alice);
+            assert_eq!(log[0].action, "set_seat_price");
+            assert_eq!(log[0].params, scale::Encode::encode(&(String::from("A1"), Some(5u128))));
+        }
+
+        #[ink::test]
+        fn ticket_payload_is_domain_separated() {
+            let erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            let payload = erc20.ticket_payload(accounts.bob, seats(&["A1"]), 7);
+            assert_eq!(
+                payload,
+                scale::Encode::encode(&(TICKET_DOMAIN, contract, accounts.bob, seats(&["A1"]), 7u64))
+            );
+            assert_ne!(
+                erc20.ticket_payload_hash(accounts.bob, seats(&["A1"]), 7),
+                erc20.ticket_payload_hash(accounts.bob, seats(&["A1"]), 8)
+            );
+            // Bob does not hold the seat, whatever he signed.
+            assert!(!erc20.verify_ticket(accounts.bob, seats(&["A1"]), 7, [0; 65]));
+        }
+
+        #[ink::test]
+        fn ticket_hash_follows_the_holder() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            let hash = erc20.ticket_hash(String::from("A1"), accounts.bob);
+            assert_ne!(hash, erc20.ticket_hash(String::from("A1"), accounts.charlie));
+            assert!(!erc20.is_valid_ticket_hash(String::from("A1"), hash));
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            assert!(erc20.is_valid_ticket_hash(String::from("A1"), hash));
+            assert!(!erc20.is_valid_ticket_hash(String::from("A2"), hash));
+        }
+
+        #[ink::test]
+        fn dual_signature_seats_cannot_be_gifted() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            assert_eq!(erc20.set_dual_signature(String::from("A1"), true), Err(Error::NotOwner));
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_dual_signature(String::from("A1"), true), Ok(()));
+            assert!(erc20.requires_dual_signature(String::from("A1")));
+
+            set_sender(accounts.bob, 0);
+            assert_eq!(
+                erc20.gift_seat(accounts.charlie, String::from("A1"), None),
+                Err(Error::DualSignatureRequired)
+            );
+            assert_eq!(
+                erc20.transfer_seat_signed(
+                    String::from("A1"),
+                    accounts.bob,
+                    accounts.charlie,
+                    0,
+                    [0; 65],
+                    [0; 65]
+                ),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn delegate_controls_lent_seat() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            assert_eq!(erc20.delegate_seat(String::from("A1"), accounts.django, 0), Err(Error::InvalidDeadline));
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.delegate_seat(String::from("A1"), accounts.django, 100), Err(Error::NotSeatOwner));
+
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.delegate_seat(String::from("A1"), accounts.django, 100), Ok(()));
+            assert_eq!(
+                erc20.seat_delegation(String::from("A1")),
+                Some(SeatDelegation {
+                    holder: accounts.bob,
+                    delegate: accounts.django,
+                    until: 100,
+                })
+            );
+            let hash = erc20.ticket_hash(String::from("A1"), accounts.django);
+            assert!(erc20.is_valid_ticket_hash(String::from("A1"), hash));
+            let hash = erc20.ticket_hash(String::from("A1"), accounts.bob);
+            assert!(!erc20.is_valid_ticket_hash(String::from("A1"), hash));
+
+            // the delegation ends when the seat changes hands
+            assert_eq!(erc20.gift_seat(accounts.charlie, String::from("A1"), None), Ok(()));
+            assert_eq!(erc20.seat_delegation(String::from("A1")), None);
+        }
+
+        #[ink::test]
+        fn check_in_per_session() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_session_count(0), Err(Error::UnknownSession(0)));
+            assert_eq!(erc20.set_session_count(2), Ok(()));
+            assert_eq!(erc20.add_verifier(accounts.django), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.check_in(String::from("A1"), 2), Err(Error::UnknownSession(2)));
+            assert_eq!(erc20.check_in(String::from("A1"), 1), Ok(()));
+            assert_eq!(erc20.check_in(String::from("A1"), 1), Err(Error::AlreadyCheckedIn));
+            assert!(!erc20.is_checked_in(String::from("A1"), 0));
+            assert_eq!(erc20.check_in(String::from("A1"), 0), Ok(()));
+
+            assert_eq!(erc20.checked_in_sessions(String::from("A1")), vec![1, 0]);
+            assert_eq!(erc20.session_checked_in_count(0), 1);
+            assert_eq!(erc20.session_checked_in_count(1), 1);
+            assert_eq!(erc20.checked_in_count(), 1);
+        }
+
+        #[ink::test]
+        fn series_dates_track_seats_separately() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.add_series_date(100), Ok(0));
+            assert_eq!(erc20.add_series_date(200), Ok(1));
+            assert_eq!(erc20.series_dates(), vec![100, 200]);
+            assert_eq!(erc20.add_verifier(accounts.django), Ok(()));
+
+            set_sender(accounts.bob, 20);
+            assert_eq!(erc20.purchase_series_seats(2, accounts.bob, seats(&["A1"])), Err(Error::UnknownDate(2)));
+            assert_eq!(
+                erc20.purchase_series_seats(0, accounts.bob, seats(&["A1", "A1"])),
+                Err(Error::DuplicateSeat(String::from("A1")))
+            );
+            assert_eq!(erc20.purchase_series_seats(0, accounts.bob, seats(&["A1", "A2"])), Ok(()));
+            set_sender(accounts.charlie, 10);
+            assert_eq!(
+                erc20.purchase_series_seats(0, accounts.charlie, seats(&["A1"])),
+                Err(Error::SeatsUnavailable(seats(&["A1"])))
+            );
+            assert_eq!(erc20.purchase_series_seats(1, accounts.charlie, seats(&["A1"])), Ok(()));
+
+            assert_eq!(erc20.series_seat_owner(0, String::from("A1")), Some(accounts.bob));
+            assert_eq!(erc20.series_seat_owner(1, String::from("A1")), Some(accounts.charlie));
+            assert_eq!(erc20.series_sold_count(0), 2);
+            assert_eq!(erc20.series_sold_count(1), 1);
+            assert!(erc20.is_seat_free(String::from("A1")));
+
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.check_in_series(1, String::from("A1")), Ok(()));
+            assert_eq!(erc20.check_in_series(1, String::from("A1")), Err(Error::AlreadyCheckedIn));
+            assert!(!erc20.is_series_checked_in(0, String::from("A1")));
+        }
+
+        #[ink::test]
+        fn series_tickets_are_throttled_and_refundable() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            assert_eq!(erc20.add_series_date(100), Ok(0));
+            assert_eq!(erc20.set_throttle(1, 10), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(erc20.purchase_series_seats(0, accounts.bob, seats(&["A1"])), Ok(()));
+            assert_eq!(
+                erc20.purchase_series_seats(0, accounts.bob, seats(&["A2"])),
+                Err(Error::RateLimited)
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
+
+            set_sender(accounts.charlie, 0);
+            assert_eq!(
+                erc20.cancel_series_ticket(0, String::from("A1"), CancelReason::Refund),
+                Err(Error::NotVerifier)
+            );
+            set_sender(accounts.alice, 0);
+            let bob_funds = funds_of(accounts.bob);
+            assert_eq!(erc20.cancel_series_ticket(0, String::from("A1"), CancelReason::Refund), Ok(()));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 10);
+            assert_eq!(erc20.series_seat_owner(0, String::from("A1")), None);
+            assert_eq!(erc20.series_sold_count(0), 0);
+            assert!(erc20.is_series_seat_available(0, String::from("A1")));
+            assert_eq!(
+                erc20.cancel_series_ticket(0, String::from("A1"), CancelReason::Refund),
+                Err(Error::SeatNotSold(String::from("A1")))
+            );
+        }
+
+        #[ink::test]
+        fn zone_check_ins_are_capped() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_zone(String::from("pit"), 2, 5), Ok(()));
+            assert_eq!(erc20.add_verifier(accounts.django), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(erc20.purchase_zone(String::from("pit"), 2), Ok(()));
+            assert_eq!(erc20.unused_zone_tickets(String::from("pit"), accounts.bob), 2);
+            // Unused zone tickets stay with the buyer.
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.transfer(accounts.charlie, 1), Err(Error::NonTransferable));
+
+            set_sender(accounts.django, 0);
+            assert_eq!(
+                erc20.check_in_zone(String::from("pit"), accounts.charlie),
+                Err(Error::NoZoneTicket(String::from("pit")))
+            );
+            assert_eq!(erc20.check_in_zone(String::from("pit"), accounts.bob), Ok(()));
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.transfer(accounts.charlie, 1), Ok(()));
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.check_in_zone(String::from("pit"), accounts.bob), Ok(()));
+            assert_eq!(
+                erc20.check_in_zone(String::from("pit"), accounts.bob),
+                Err(Error::ZoneFull(String::from("pit")))
+            );
+            assert_eq!(erc20.zone_checked_in_count(String::from("pit")), 2);
+            assert_eq!(erc20.unused_zone_tickets(String::from("pit"), accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn accessible_seats_require_eligibility() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_accessible_seats(seats(&["A1"]), true), Ok(()));
+            assert_eq!(erc20.add_verifier(accounts.django), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Err(Error::AccessibilityRequired(String::from("A1")))
+            );
+            assert!(erc20.is_seat_available(seats(&["A1"])));
+
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.attest_accessibility(accounts.bob, true), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn concession_category_is_charged_and_recorded() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_category_price(BuyerCategory::Child, 10_001), Err(Error::InvalidCategoryPrice));
+            assert_eq!(erc20.set_category_price(BuyerCategory::Child, 5_000), Ok(()));
+            assert_eq!(erc20.add_verifier(accounts.django), Ok(()));
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.attest_category(accounts.bob, BuyerCategory::Child), Ok(()));
+
+            // Quotes follow the category of the recipient, not of the caller.
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.quote(accounts.bob, seats(&["A1"]), None), Ok(5));
+            assert_eq!(erc20.quote(accounts.charlie, seats(&["A1"]), None), Ok(10));
+            set_sender(accounts.bob, 10);
+            assert!(erc20
+                .purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None)
+                .is_err());
+            set_sender(accounts.bob, 5);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            let purchase = erc20.seat_purchase(String::from("A1")).unwrap();
+            assert_eq!(purchase.category, BuyerCategory::Child);
+            assert_eq!(purchase.price, 5);
+        }
+
+        #[ink::test]
+        fn surge_pricing_raises_prices_with_demand() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            let pricing = SurgePricing {
+                step_bps: 1_000,
+                step_seats: 2,
+                window: 100,
+                floor_bps: 8_000,
+                ceiling_bps: 11_000,
+            };
+            assert_eq!(
+                erc20.set_surge_pricing(Some(SurgePricing {
+                    ceiling_bps: 9_000,
+                    ..pricing.clone()
+                })),
+                Err(Error::InvalidSurgePricing)
+            );
+            assert_eq!(erc20.set_surge_pricing(Some(pricing)), Ok(()));
+            assert_eq!(erc20.current_price(), 10);
+
+            set_sender(accounts.bob, 20);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Ok(())
+            );
+            assert_eq!(erc20.surge_bps(), 11_000);
+            assert_eq!(erc20.current_price(), 11);
+            assert_eq!(erc20.quote(accounts.bob, seats(&["A3"]), None), Ok(11));
+        }
+
+        #[ink::test]
+        fn general_admission_follows_concession_and_surge_pricing() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_general_admission(10, 10), Ok(()));
+            assert_eq!(erc20.set_category_price(BuyerCategory::Child, 5_000), Ok(()));
+            assert_eq!(erc20.add_verifier(accounts.django), Ok(()));
+            assert_eq!(
+                erc20.set_surge_pricing(Some(SurgePricing {
+                    step_bps: 1_000,
+                    step_seats: 2,
+                    window: 100,
+                    floor_bps: 8_000,
+                    ceiling_bps: 12_000,
+                })),
+                Ok(())
+            );
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.attest_category(accounts.bob, BuyerCategory::Child), Ok(()));
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(erc20.purchase_tickets(accounts.bob, 2, vec![1], vec![], None), Ok(()));
+            assert_eq!(erc20.surge_bps(), 11_000);
+
+            // The sale raised the price and the recipient pays the full price.
+            set_sender(accounts.charlie, 22);
+            assert_eq!(erc20.purchase_tickets(accounts.charlie, 2, vec![1], vec![], None), Ok(()));
+            assert_eq!(erc20.general_admission(), (10, 4, 10));
+            assert_eq!(erc20.surge_bps(), 12_000);
+        }
+
+        #[ink::test]
+        fn tiers_set_seat_prices() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.set_tier(String::from(DEFAULT_TIER), 20, seats(&["A1"])),
+                Err(Error::ReservedTier)
+            );
+            assert_eq!(
+                erc20.set_tier(String::from("VIP"), 20, seats(&["Z9"])),
+                Err(Error::UnknownSeat(String::from("Z9")))
+            );
+            assert_eq!(erc20.set_tier(String::from("VIP"), 20, seats(&["A1"])), Ok(()));
+            assert_eq!(erc20.quote(accounts.bob, seats(&["A1", "A2"]), None), Ok(30));
+
+            set_sender(accounts.bob, 30);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 2, vec![1], seats(&["A1", "A2"]), None),
+                Ok(())
+            );
+            assert_eq!(
+                erc20.revenue_by_tier(),
+                vec![(String::from(DEFAULT_TIER), 10), (String::from("VIP"), 20)]
+            );
+        }
+
+        #[ink::test]
+        fn migrate_storage_counts_seats() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.migrate_storage(), Err(Error::AlreadyMigrated));
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+
+            set_sender(accounts.alice, 0);
+            erc20.storage_version = 0;
+            assert_eq!(erc20.migrate_storage(), Err(Error::UnknownStorageVersion(0)));
+            assert_eq!(erc20.storage_version(), 0);
+
+            erc20.storage_version = 1;
+            erc20.seats_sold = 0;
+            assert_eq!(erc20.migrate_storage(), Ok(()));
+            assert_eq!(erc20.storage_version(), STORAGE_VERSION);
+            assert_eq!(erc20.seats_sold, 1);
+        }
+
+        #[ink::test]
+        fn resales_respect_the_floor() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(
+                erc20.set_resale_floor(Some(ResaleFloor::FaceValueBps(10_001))),
+                Err(Error::InvalidResaleFloor)
+            );
+            assert_eq!(erc20.set_resale_floor(Some(ResaleFloor::FaceValueBps(8_000))), Ok(()));
+            assert_eq!(erc20.min_resale_price(String::from("A1")), Ok(8));
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            set_sender(accounts.bob, 0);
+            assert_eq!(
+                erc20.offer_seat(String::from("A1"), accounts.charlie, 7, 100),
+                Err(Error::BelowResaleFloor { minimum: 8 })
+            );
+            assert_eq!(erc20.offer_seat(String::from("A1"), accounts.charlie, 8, 100), Ok(()));
+
+            set_sender(accounts.django, 5);
+            assert_eq!(erc20.make_offer(String::from("A1")), Err(Error::BelowResaleFloor { minimum: 8 }));
+        }
+
+        /// Returns the native balance of `account`.
+        fn funds_of(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        /// Advances blocks until at least `duration` milliseconds have passed.
+        fn advance_time(duration: Timestamp) {
+            let until = ink_env::block_timestamp::<ink_env::DefaultEnvironment>() + duration;
+            while ink_env::block_timestamp::<ink_env::DefaultEnvironment>() < until {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot advance block");
+            }
+        }
+
+        #[ink::test]
+        fn zone_purchases_draw_down_capacity() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_zone(String::from("pit"), 2, 5), Ok(()));
+            set_sender(accounts.bob, 5);
+            assert_eq!(
+                erc20.purchase_zone(String::from("floor"), 1),
+                Err(Error::UnknownZone(String::from("floor")))
+            );
+            assert_eq!(
+                erc20.purchase_zone(String::from("pit"), 2),
+                Err(Error::IncorrectPrice {
+                    expected: 10,
+                    provided: 5,
+                })
+            );
+            set_sender(accounts.bob, 10);
+            assert_eq!(erc20.purchase_zone(String::from("pit"), 2), Ok(()));
+            assert_eq!(erc20.zone(String::from("pit")).map(|zone| zone.sold), Some(2));
+            assert_eq!(erc20.balance_of(accounts.bob), 2);
+            assert_eq!(erc20.total_supply(), 5);
+            assert_eq!(erc20.total_paid(accounts.bob), 10);
+
+            set_sender(accounts.charlie, 5);
+            assert_eq!(
+                erc20.purchase_zone(String::from("pit"), 1),
+                Err(Error::ZoneSoldOut {
+                    zone: String::from("pit"),
+                    remaining: 0,
+                })
+            );
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_zone(String::from("pit"), 1, 5), Err(Error::InvalidCapacity));
+            assert_eq!(erc20.set_zone(String::from("pit"), 3, 5), Ok(()));
+            set_sender(accounts.charlie, 5);
+            assert_eq!(erc20.purchase_zone(String::from("pit"), 1), Ok(()));
+            assert_eq!(erc20.zone(String::from("pit")).map(|zone| zone.sold), Some(3));
+        }
+
+        #[ink::test]
+        fn lottery_assigns_seats_and_refunds_losers() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let seed = [7; 32];
+            let entry_end = ink_env::block_timestamp::<ink_env::DefaultEnvironment>();
+
+            let seed_hash = erc20.lottery_seed_hash(seed);
+            assert_eq!(erc20.start_lottery(seats(&["A1"]), 5, entry_end, seed_hash), Ok(()));
+            assert!(!erc20.is_seat_available(seats(&["A1"])));
+
+            set_sender(accounts.bob, 5);
+            assert_eq!(erc20.enter_lottery(), Ok(()));
+            assert_eq!(erc20.enter_lottery(), Err(Error::AlreadyEntered));
+            set_sender(accounts.charlie, 4);
+            assert_eq!(
+                erc20.enter_lottery(),
+                Err(Error::IncorrectPrice {
+                    expected: 5,
+                    provided: 4,
+                })
+            );
+            set_sender(accounts.charlie, 5);
+            assert_eq!(erc20.enter_lottery(), Ok(()));
+            assert_eq!(erc20.draw_lottery(seed), Err(Error::LotteryOpen));
+            assert_eq!(erc20.claim_lottery_refund(), Err(Error::LotteryNotDrawn));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
+
+            advance_time(1);
+            set_sender(accounts.django, 5);
+            assert_eq!(erc20.enter_lottery(), Err(Error::LotteryClosed));
+            assert_eq!(erc20.draw_lottery([8; 32]), Err(Error::InvalidLotterySeed));
+            assert_eq!(erc20.draw_lottery(seed), Ok(()));
+            assert_eq!(erc20.draw_lottery(seed), Err(Error::LotteryClosed));
+
+            let winner = erc20.seat_owner(String::from("A1")).expect("no lottery winner");
+            let loser = if winner == accounts.bob { accounts.charlie } else { accounts.bob };
+            assert_eq!(erc20.balance_of(winner), 1);
+            assert_eq!(erc20.total_paid(winner), 5);
+            assert_eq!(erc20.lottery_deposit_of(winner), 0);
+
+            let loser_funds = funds_of(loser);
+            set_sender(loser, 0);
+            assert_eq!(erc20.claim_lottery_refund(), Ok(()));
+            assert_eq!(funds_of(loser), loser_funds + 5);
+            assert_eq!(erc20.claim_lottery_refund(), Err(Error::NoDeposit));
+            set_sender(winner, 0);
+            assert_eq!(erc20.claim_lottery_refund(), Err(Error::NoDeposit));
+        }
+
+        #[ink::test]
+        fn auction_refunds_outbid_bidders() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            assert_eq!(erc20.start_auction(String::from("A1"), 5, 0), Err(Error::InvalidAuction));
+            assert_eq!(
+                erc20.start_auction(String::from("Z9"), 5, 1),
+                Err(Error::UnknownSeat(String::from("Z9")))
+            );
+            assert_eq!(erc20.start_auction(String::from("A1"), 5, 1), Ok(()));
+            assert!(!erc20.is_seat_available(seats(&["A1"])));
+
+            set_sender(accounts.bob, 4);
+            assert_eq!(erc20.bid(String::from("A1")), Err(Error::BidTooLow { minimum: 5 }));
+            set_sender(accounts.bob, 5);
+            assert_eq!(erc20.bid(String::from("A1")), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 5)
+                .expect("Cannot set account balance");
+            let bob_funds = funds_of(accounts.bob);
+
+            set_sender(accounts.charlie, 5);
+            assert_eq!(erc20.bid(String::from("A1")), Err(Error::BidTooLow { minimum: 6 }));
+            set_sender(accounts.charlie, 7);
+            assert_eq!(erc20.bid(String::from("A1")), Ok(()));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 5);
+            assert_eq!(erc20.settle_auction(String::from("A1")), Err(Error::AuctionNotEnded));
+
+            advance_time(2);
+            set_sender(accounts.bob, 8);
+            assert_eq!(erc20.bid(String::from("A1")), Err(Error::AuctionEnded));
+            assert_eq!(erc20.settle_auction(String::from("A1")), Ok(()));
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.charlie));
+            assert_eq!(erc20.balance_of(accounts.charlie), 1);
+            assert_eq!(erc20.total_paid(accounts.charlie), 7);
+            assert_eq!(erc20.settle_auction(String::from("A1")), Err(Error::UnknownAuction));
+        }
+
+        #[ink::test]
+        fn undeliverable_auction_wins_are_refunded() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
 
-            }
+            assert_eq!(erc20.start_auction(String::from("A1"), 5, 1), Ok(()));
+            set_sender(accounts.bob, 5);
+            assert_eq!(erc20.bid(String::from("A1")), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 5)
+                .expect("Cannot set account balance");
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.blacklist(accounts.bob), Ok(()));
+
+            advance_time(2);
+            let bob_funds = funds_of(accounts.bob);
+            assert_eq!(erc20.settle_auction(String::from("A1")), Ok(()));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 5);
+            assert_eq!(erc20.seat_owner(String::from("A1")), None);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert!(erc20.is_seat_available(seats(&["A1"])));
+            assert_eq!(erc20.settle_auction(String::from("A1")), Err(Error::UnknownAuction));
         }
 
+        #[ink::test]
+        fn expired_holds_pay_the_bounty() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
 
-        /// Transfers `value` amount of tokens from the caller's account to account `to`.
-        ///
-        /// On success a `Transfer` event is emitted.
-        ///
-        /// # Errors
-        ///
-        /// Returns `InsufficientBalance` error if there are not enough tokens on
-        /// the caller's account balance.
-        #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
-            let from = self.env().caller();
-            self.transfer_from_to(&from, &to, value)
-        }
+            set_sender(accounts.bob, 4);
+            assert_eq!(erc20.hold_seats(seats(&["A1"])), Err(Error::HoldsDisabled));
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_hold_policy(1, 4, 5), Err(Error::InvalidHoldPolicy));
+            assert_eq!(erc20.set_hold_policy(1, 4, 1), Ok(()));
 
+            set_sender(accounts.bob, 3);
+            assert_eq!(
+                erc20.hold_seats(seats(&["A1"])),
+                Err(Error::IncorrectPrice {
+                    expected: 4,
+                    provided: 3,
+                })
+            );
+            set_sender(accounts.bob, 4);
+            assert_eq!(erc20.hold_seats(seats(&["A1"])), Ok(()));
+            set_sender(accounts.charlie, 4);
+            assert_eq!(
+                erc20.hold_seats(seats(&["A1"])),
+                Err(Error::SeatsUnavailable(seats(&["A1"])))
+            );
+            assert_eq!(erc20.release_hold(String::from("A1")), Err(Error::NoHold));
+            assert_eq!(erc20.hold_seats(seats(&["A2"])), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 8)
+                .expect("Cannot set account balance");
 
-                // check how this work, do we actually need check2 or does the function end when we return the error
-                #[ink(message,payable)]
-                pub fn purchase_tickets(&mut self,to: AccountId, value: Balance, signature: Vec<u8>, seats: Vec<String>) -> Result<()> {
-                    self.proof_key.insert(to,signature);
-        
-        
-                    if self.price*value != self.env().transferred_balance() {
-                        return Err(Error::IncorrectPrice);
-                    }else{
-        
-                        if(self.has_seats==true){
-                        if !self.is_seat_available(seats.clone()) {
-                                return Err(Error::SeatTaken)
-                        }
-                        
-                        if value==seats.clone().len() as u128{
-                        for f in seats.clone().iter(){
-                            self.seat_taken.insert(f.clone(),true);
-                        }
-                    }
-                    // add tokens to balance
-        
-                    self.transfer_from_to(&self.get_owner(),&to, value);
-                    self.contract_balance += self.env().transferred_balance();
-                        Ok(())
-        
-                    
-        
-                        }else{
-                         return Err(Error::SeatMismatch)
-                         }
-                    }
-                }
-        
-                #[ink(message)]
-                pub fn is_seat_available(&mut self, seats:Vec<String>) -> bool {
-                    for f in seats.iter() {
-                        if *self.seat_taken.get(f).unwrap_or(&true) {
-                            return false
-                        }
-                    }
-                    return true;
-                }
-        
+            // Releasing a hold pays back the whole deposit.
+            let charlie_funds = funds_of(accounts.charlie);
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.release_hold(String::from("A2")), Ok(()));
+            assert_eq!(funds_of(accounts.charlie), charlie_funds + 4);
 
-                       // little comment
-        #[ink(message,payable)]
-        pub fn clear(&mut self) -> Result<()> {
-            let check = self.env().caller();
-            if check != self.owner {
-                //fix this error
-                return Err(Error::IncorrectPrice);
-            }else{
-                // dont have to check this
-            self.env().transfer(self.owner,self.contract_balance);
-            self.contract_balance = 0;
-            Ok(()) 
-            }
+            // Holds that have not expired are skipped.
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.expire_holds(seats(&["A1"])), Ok(()));
+            assert!(erc20.seat_hold(String::from("A1")).is_some());
 
-        }
- 
-        /// Allows `spender` to withdraw from the caller's account multiple times, up to
-        /// the `value` amount.
-        ///
-        /// If this function is called again it overwrites the current allowance with `value`.
-        ///
-        /// An `Approval` event is emitted.
-        #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
-            let owner = self.env().caller();
-            self.allowances.insert((&owner, &spender), &value);
-            self.env().emit_event(Approval {
-                owner,
-                spender,
-                value,
-            });
-            Ok(())
+            advance_time(2);
+            let bob_funds = funds_of(accounts.bob);
+            let django_funds = funds_of(accounts.django);
+            assert_eq!(erc20.expire_holds(seats(&["A1"])), Ok(()));
+            assert_eq!(funds_of(accounts.django), django_funds + 1);
+            assert_eq!(funds_of(accounts.bob), bob_funds + 3);
+            assert_eq!(erc20.seat_hold(String::from("A1")), None);
+            assert!(erc20.is_seat_free(String::from("A1")));
         }
 
-        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
-        ///
-        /// This can be used to allow a contract to transfer tokens on ones behalf and/or
-        /// to charge fees in sub-currencies, for example.
-        ///
-        /// On success a `Transfer` event is emitted.
-        ///
-        /// # Errors
-        ///
-        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
-        /// for the caller to withdraw from `from`.
-        ///
-        /// Returns `InsufficientBalance` error if there are not enough tokens on
-        /// the account balance of `from`.
-        #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
-            value: Balance,
-        ) -> Result<()> {
-            let caller = self.env().caller();
-            let allowance = self.allowance_impl(&from, &caller);
-            if allowance < value {
-                return Err(Error::InsufficientAllowance)
-            }
-            self.transfer_from_to(&from, &to, value)?;
-            self.allowances
-                .insert((&from, &caller), &(allowance - value));
-            Ok(())
-        }
+        #[ink::test]
+        fn commitments_are_honored_in_commit_order() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let bob_hash = erc20.commitment_hash(accounts.bob, seats(&["A1"]), [1; 32]);
+            let charlie_hash = erc20.commitment_hash(accounts.charlie, seats(&["A1"]), [2; 32]);
 
+            set_sender(accounts.bob, 15);
+            assert_eq!(erc20.commit_purchase(bob_hash), Err(Error::CommitRevealDisabled));
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_reveal_window(1, 5), Ok(()));
+            set_sender(accounts.bob, 4);
+            assert_eq!(
+                erc20.commit_purchase(bob_hash),
+                Err(Error::IncorrectPrice { expected: 5, provided: 4 })
+            );
+            set_sender(accounts.bob, 15);
+            assert_eq!(erc20.commit_purchase(bob_hash), Ok(()));
+            assert_eq!(erc20.commit_purchase(bob_hash), Err(Error::AlreadyCommitted));
+            assert_eq!(erc20.reveal_purchase(seats(&["A1"]), [1; 32]), Err(Error::RevealTooEarly));
+            set_sender(accounts.charlie, 10);
+            assert_eq!(erc20.commit_purchase(charlie_hash), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 25)
+                .expect("Cannot set account balance");
 
-        #[ink(message)]
-        pub fn burn(&mut self, from: AccountId, value: Balance) -> Result<()> {
-            let caller = self.env().caller();
-            let is_ver = *self.verifier.get(&caller).unwrap_or(&false);
-            if is_ver == true {
-            let allowance = self.balances.get(&from).unwrap_or_default();
-            if allowance < value {
-                return Err(Error::InsufficientAllowance)
-            }else{
-                self.balances.insert(from, &(allowance - value));
-                Ok(())
-            }
-            } else {
-                return Err(Error::NotVerifier);
-            }
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.reveal_purchase(seats(&["A1"]), [1; 32]), Err(Error::InvalidReveal));
+            assert_eq!(erc20.reveal_purchase(seats(&["A1"]), [2; 32]), Ok(()));
+            // The earlier commitment takes the seat over.
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.reveal_purchase(seats(&["A1"]), [1; 32]), Ok(()));
+            assert_eq!(erc20.settle_commitment(accounts.bob), Err(Error::RevealWindowOpen));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let bob_funds = funds_of(accounts.bob);
+            let charlie_funds = funds_of(accounts.charlie);
+            assert_eq!(erc20.settle_commitment(accounts.bob), Ok(()));
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.bob));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 5);
+            assert_eq!(erc20.total_paid(accounts.bob), 10);
+            assert_eq!(erc20.settle_commitment(accounts.charlie), Ok(()));
+            assert_eq!(funds_of(accounts.charlie), charlie_funds + 10);
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+            assert_eq!(erc20.settle_commitment(accounts.charlie), Err(Error::NoCommitment));
         }
 
+        #[ink::test]
+        fn reveals_must_be_covered_by_the_deposit() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let hash = erc20.commitment_hash(accounts.bob, seats(&["A1"]), [1; 32]);
 
-        /// Transfers `value` amount of tokens from the caller's account to account `to`.
-        ///
-        /// On success a `Transfer` event is emitted.
-        ///
-        /// # Errors
-        ///
-        /// Returns `InsufficientBalance` error if there are not enough tokens on
-        /// the caller's account balance.
-        fn transfer_from_to(
-            &mut self,
-            from: &AccountId,
-            to: &AccountId,
-            value: Balance,
-        ) -> Result<()> {
-            let from_balance = self.balance_of_impl(from);
-            if from_balance < value {
-                return Err(Error::InsufficientBalance)
-            }
+            assert_eq!(erc20.set_reveal_window(1, 5), Ok(()));
+            assert_eq!(erc20.set_service_fee(1_000, Some(accounts.django)), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(erc20.commit_purchase(hash), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
 
-            self.balances.insert(from, &(from_balance - value));
-            let to_balance = self.balance_of_impl(to);
-            self.balances.insert(to, &(to_balance + value));
-            self.env().emit_event(Transfer {
-                from: Some(*from),
-                to: Some(*to),
-                value,
-            });
-            Ok(())
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            set_sender(accounts.bob, 0);
+            // The deposit covers the seat but not the service fee.
+            assert_eq!(
+                erc20.reveal_purchase(seats(&["A1"]), [1; 32]),
+                Err(Error::IncorrectPrice { expected: 11, provided: 10 })
+            );
+            assert_eq!(erc20.commitment(accounts.bob).map(|c| c.revealed), Some(false));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let bob_funds = funds_of(accounts.bob);
+            assert_eq!(erc20.settle_commitment(accounts.bob), Ok(()));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 10);
+            assert_eq!(erc20.seat_owner(String::from("A1")), None);
         }
-    }
 
-    /// Unit tests.
-    #[cfg(not(feature = "ink-experimental-engine"))]
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
+        #[ink::test]
+        fn invoices_reserve_seats_until_the_deadline() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>();
 
-        type Event = <Erc20 as ::ink_lang::reflect::ContractEventBase>::Type;
+            assert_eq!(erc20.create_invoice(accounts.bob, seats(&["A1"]), now), Err(Error::InvalidDeadline));
+            let paid = erc20
+                .create_invoice(accounts.bob, seats(&["A1"]), now + 1_000_000)
+                .expect("invoice not created");
+            let lapsed = erc20
+                .create_invoice(accounts.bob, seats(&["A2"]), now + 1)
+                .expect("invoice not created");
+            assert_eq!(
+                erc20.create_invoice(accounts.charlie, seats(&["A1"]), now + 1),
+                Err(Error::SeatsUnavailable(seats(&["A1"])))
+            );
 
-        use ink_lang as ink;
+            set_sender(accounts.charlie, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 1, vec![1], seats(&["A1"]), None),
+                Err(Error::SeatsUnavailable(seats(&["A1"])))
+            );
+            assert_eq!(erc20.settle_invoice(paid), Err(Error::NotInvoiceBuyer));
+            set_sender(accounts.bob, 9);
+            assert_eq!(
+                erc20.settle_invoice(paid),
+                Err(Error::IncorrectPrice {
+                    expected: 10,
+                    provided: 9,
+                })
+            );
+            set_sender(accounts.bob, 10);
+            assert_eq!(erc20.settle_invoice(paid), Ok(()));
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.bob));
+            assert_eq!(erc20.total_paid(accounts.bob), 10);
+            assert_eq!(erc20.invoice(paid), None);
 
-        fn assert_transfer_event(
-            event: &ink_env::test::EmittedEvent,
-            expected_from: Option<AccountId>,
-            expected_to: Option<AccountId>,
-            expected_value: Balance,
-        ) {
-            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
-                .expect("encountered invalid contract event data buffer");
-            if let Event::Transfer(Transfer { from, to, value }) = decoded_event {
-                assert_eq!(from, expected_from, "encountered invalid Transfer.from");
-                assert_eq!(to, expected_to, "encountered invalid Transfer.to");
-                assert_eq!(value, expected_value, "encountered invalid Trasfer.value");
-            } else {
-                panic!("encountered unexpected event kind: expected a Transfer event")
-            }
-            let expected_topics = vec![
-                encoded_into_hash(&PrefixedValue {
-                    value: b"Erc20::Transfer",
-                    prefix: b"",
-                }),
-                encoded_into_hash(&PrefixedValue {
-                    prefix: b"Erc20::Transfer::from",
-                    value: &expected_from,
-                }),
-                encoded_into_hash(&PrefixedValue {
-                    prefix: b"Erc20::Transfer::to",
-                    value: &expected_to,
-                }),
-                encoded_into_hash(&PrefixedValue {
-                    prefix: b"Erc20::Transfer::value",
-                    value: &expected_value,
-                }),
-            ];
-            for (n, (actual_topic, expected_topic)) in
-                event.topics.iter().zip(expected_topics).enumerate()
-            {
-                let topic = actual_topic
-                    .decode::<Hash>()
-                    .expect("encountered invalid topic encoding");
-                assert_eq!(topic, expected_topic, "encountered invalid topic at {}", n);
-            }
+            // Unpaid seats go back on sale at the deadline.
+            advance_time(2);
+            assert_eq!(erc20.settle_invoice(lapsed), Err(Error::InvoiceExpired));
+            set_sender(accounts.charlie, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.charlie, 1, vec![1], seats(&["A2"]), None),
+                Ok(())
+            );
         }
 
-        /// The default constructor does its job.
         #[ink::test]
-        fn new_works() {
-            // Constructor works.
-            let _erc20 = Erc20::new(100);
+        fn installments_sell_the_seat_once_paid() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
 
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(1, emitted_events.len());
+            set_sender(accounts.bob, 5);
+            assert_eq!(erc20.start_installments(String::from("A1")), Err(Error::InstallmentsDisabled));
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_installment_policy(1, 1_000_000, 5_000), Err(Error::InvalidInstallments));
+            assert_eq!(erc20.set_installment_policy(2, 1_000_000, 5_000), Ok(()));
 
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+            set_sender(accounts.bob, 4);
+            assert_eq!(
+                erc20.start_installments(String::from("A1")),
+                Err(Error::IncorrectPrice {
+                    expected: 5,
+                    provided: 4,
+                })
+            );
+            set_sender(accounts.bob, 5);
+            assert_eq!(erc20.start_installments(String::from("A1")), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            set_sender(accounts.charlie, 5);
+            assert_eq!(
+                erc20.start_installments(String::from("A1")),
+                Err(Error::SeatsUnavailable(seats(&["A1"])))
             );
+            assert_eq!(erc20.pay_installment(String::from("A1")), Err(Error::NoInstallmentPlan));
+            assert_eq!(erc20.forfeit_installments(String::from("A1")), Err(Error::InstallmentNotOverdue));
+
+            set_sender(accounts.bob, 6);
+            assert_eq!(
+                erc20.pay_installment(String::from("A1")),
+                Err(Error::IncorrectPrice {
+                    expected: 5,
+                    provided: 6,
+                })
+            );
+            set_sender(accounts.bob, 5);
+            assert_eq!(erc20.pay_installment(String::from("A1")), Ok(()));
+            assert_eq!(erc20.installment_plan(String::from("A1")), None);
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.bob));
+            assert_eq!(erc20.balance_of(accounts.bob), 1);
+            assert_eq!(erc20.total_paid(accounts.bob), 10);
         }
 
-        /// The total supply was applied.
         #[ink::test]
-        fn total_supply_works() {
-            // Constructor works.
-            let erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+        fn missed_installments_are_forfeited() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+
+            assert_eq!(erc20.set_installment_policy(2, 1, 5_000), Ok(()));
+            set_sender(accounts.bob, 5);
+            assert_eq!(erc20.start_installments(String::from("A1")), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 5)
+                .expect("Cannot set account balance");
+
+            advance_time(2);
+            assert_eq!(erc20.pay_installment(String::from("A1")), Err(Error::InstallmentOverdue));
+            let bob_funds = funds_of(accounts.bob);
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.forfeit_installments(String::from("A1")), Ok(()));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 3);
+            assert_eq!(erc20.total_paid(accounts.bob), 2);
+            assert_eq!(erc20.withdrawable_balance(), 2);
+            assert!(erc20.is_seat_free(String::from("A1")));
+            assert_eq!(erc20.forfeit_installments(String::from("A1")), Err(Error::NoInstallmentPlan));
+        }
+
+        #[ink::test]
+        fn seat_sales_move_funds_and_refunds() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>();
+
+            assert_eq!(erc20.set_royalty(1_000), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+
+            set_sender(accounts.charlie, 0);
+            assert_eq!(
+                erc20.offer_seat(String::from("A1"), accounts.charlie, 12, now + 1_000_000),
+                Err(Error::NotSeatOwner)
+            );
+            set_sender(accounts.bob, 0);
+            assert_eq!(
+                erc20.offer_seat(String::from("A1"), accounts.charlie, 12, now),
+                Err(Error::InvalidDeadline)
+            );
+            assert_eq!(erc20.offer_seat(String::from("A1"), accounts.charlie, 12, now + 1_000_000), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 22)
+                .expect("Cannot set account balance");
+
+            set_sender(accounts.django, 12);
+            assert_eq!(erc20.accept_seat_sale(String::from("A1")), Err(Error::NotSaleBuyer));
+            assert_eq!(erc20.cancel_seat_sale(String::from("A1")), Err(Error::NoSeatSale));
+            set_sender(accounts.charlie, 11);
+            assert_eq!(
+                erc20.accept_seat_sale(String::from("A1")),
+                Err(Error::IncorrectPrice {
+                    expected: 12,
+                    provided: 11,
+                })
             );
-            // Get the token total supply.
-            assert_eq!(erc20.total_supply(), 100);
+            let bob_funds = funds_of(accounts.bob);
+            set_sender(accounts.charlie, 12);
+            assert_eq!(erc20.accept_seat_sale(String::from("A1")), Ok(()));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 11);
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.charlie));
+            assert_eq!(erc20.balance_of(accounts.charlie), 1);
+            assert_eq!(erc20.seat_sale(String::from("A1")), None);
+
+            // Refunds follow the seat to its new holder, up to the original price.
+            let purchase = erc20.seat_purchase(String::from("A1")).unwrap();
+            assert_eq!(purchase.payer, accounts.charlie);
+            assert_eq!(purchase.price, 10);
+            assert_eq!(erc20.total_paid(accounts.bob), 0);
+            let charlie_funds = funds_of(accounts.charlie);
+            set_sender(accounts.alice, 0);
+            assert_eq!(
+                erc20.cancel_ticket(accounts.charlie, String::from("A1"), CancelReason::Fraud),
+                Ok(())
+            );
+            assert_eq!(funds_of(accounts.charlie), charlie_funds + 10);
+            assert_eq!(erc20.withdrawable_balance(), 1);
         }
 
-        /// Get the actual balance of an account.
         #[ink::test]
-        fn balance_of_works() {
-            // Constructor works
-            let erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
+        fn lapsed_seat_sales_cannot_be_accepted() {
+            let mut erc20 = seated_erc20();
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
-            // Alice owns all the tokens on contract instantiation
-            assert_eq!(erc20.balance_of(accounts.alice), 100);
-            // Bob does not owns tokens
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.offer_seat(String::from("A1"), accounts.charlie, 12, now + 1), Ok(()));
+            assert_eq!(erc20.cancel_seat_sale(String::from("A1")), Ok(()));
+            assert_eq!(erc20.offer_seat(String::from("A1"), accounts.charlie, 12, now + 1), Ok(()));
+
+            advance_time(2);
+            set_sender(accounts.charlie, 12);
+            assert_eq!(erc20.accept_seat_sale(String::from("A1")), Err(Error::SaleExpired));
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.bob));
         }
 
         #[ink::test]
-        fn transfer_works() {
-            // Constructor works.
-            let mut erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction.
+        fn accepted_offers_swap_seat_and_escrow() {
+            let mut erc20 = seated_erc20();
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
 
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
-            // Alice transfers 10 tokens to Bob.
-            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
-            // Bob owns 10 tokens.
-            assert_eq!(erc20.balance_of(accounts.bob), 10);
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            assert_eq!(erc20.make_offer(String::from("A1")), Err(Error::OfferOnOwnSeat));
 
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 2);
-            // Check first transfer event related to ERC-20 instantiation.
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+            set_sender(accounts.charlie, 8);
+            assert_eq!(
+                erc20.make_offer(String::from("A2")),
+                Err(Error::SeatNotSold(String::from("A2")))
             );
-            // Check the second transfer event relating to the actual trasfer.
-            assert_transfer_event(
-                &emitted_events[1],
-                Some(AccountId::from([0x01; 32])),
-                Some(AccountId::from([0x02; 32])),
-                10,
+            assert_eq!(erc20.make_offer(String::from("A1")), Ok(()));
+            set_sender(accounts.charlie, 0);
+            assert_eq!(
+                erc20.make_offer(String::from("A1")),
+                Err(Error::IncorrectPrice {
+                    expected: 1,
+                    provided: 0,
+                })
+            );
+            set_sender(accounts.charlie, 4);
+            assert_eq!(erc20.make_offer(String::from("A1")), Ok(()));
+            set_sender(accounts.django, 9);
+            assert_eq!(erc20.make_offer(String::from("A1")), Ok(()));
+            assert_eq!(
+                erc20.offers(String::from("A1")),
+                vec![(accounts.charlie, 12), (accounts.django, 9)]
             );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 31)
+                .expect("Cannot set account balance");
+
+            let django_funds = funds_of(accounts.django);
+            set_sender(accounts.django, 0);
+            assert_eq!(erc20.withdraw_offer(String::from("A1")), Ok(()));
+            assert_eq!(funds_of(accounts.django), django_funds + 9);
+            assert_eq!(erc20.withdraw_offer(String::from("A1")), Err(Error::NoOffer));
+
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.accept_offer(String::from("A1"), accounts.charlie), Err(Error::NotSeatOwner));
+            let bob_funds = funds_of(accounts.bob);
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.accept_offer(String::from("A1"), accounts.django), Err(Error::NoOffer));
+            assert_eq!(erc20.accept_offer(String::from("A1"), accounts.charlie), Ok(()));
+            assert_eq!(funds_of(accounts.bob), bob_funds + 12);
+            assert_eq!(erc20.seat_owner(String::from("A1")), Some(accounts.charlie));
+            assert_eq!(erc20.balance_of(accounts.charlie), 1);
+            assert_eq!(erc20.offers(String::from("A1")), Vec::new());
+
+            let purchase = erc20.seat_purchase(String::from("A1")).unwrap();
+            assert_eq!(purchase.payer, accounts.charlie);
+            assert_eq!(purchase.price, 10);
+            assert_eq!(erc20.total_paid(accounts.bob), 0);
+            assert_eq!(erc20.total_paid(accounts.charlie), 10);
         }
 
         #[ink::test]
-        fn invalid_transfer_should_fail() {
-            // Constructor works.
-            let mut erc20 = Erc20::new(100);
+        fn dutch_auctions_replace_their_seats() {
+            let mut erc20 = seated_erc20();
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
 
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>();
-            // Create call
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
+            assert_eq!(erc20.start_dutch_auction(seats(&["A1"]), 20, 5, 1), Ok(()));
+            assert_eq!(erc20.quote(accounts.bob, seats(&["A1"]), None), Ok(20));
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A2"]), None),
+                Ok(())
             );
 
-            // Bob fails to transfers 10 tokens to Eve.
+            set_sender(accounts.alice, 0);
             assert_eq!(
-                erc20.transfer(accounts.eve, 10),
-                Err(Error::InsufficientBalance)
+                erc20.start_dutch_auction(seats(&["A2", "A3"]), 20, 5, 1),
+                Err(Error::SeatsUnavailable(seats(&["A2"])))
             );
-            // Alice owns all the tokens.
-            assert_eq!(erc20.balance_of(accounts.alice), 100);
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
-            assert_eq!(erc20.balance_of(accounts.eve), 0);
+            assert_eq!(erc20.start_dutch_auction(seats(&["A3"]), 30, 5, 1), Ok(()));
+            // The seat of the replaced auction is back at its regular price.
+            assert_eq!(erc20.quote(accounts.bob, seats(&["A1"]), None), Ok(10));
+            assert_eq!(erc20.quote(accounts.bob, seats(&["A3"]), None), Ok(30));
+        }
 
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 1);
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+        #[ink::test]
+        fn tier_prices_are_updated_in_bulk() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            assert_eq!(erc20.set_tier(String::from("VIP"), 20, seats(&["A1"])), Ok(()));
+            assert_eq!(erc20.set_tier(String::from("balcony"), 5, seats(&["A2"])), Ok(()));
+            assert_eq!(
+                erc20.set_prices_batch(vec![
+                    (String::from("VIP"), 30),
+                    (String::from("pit"), 8),
+                ]),
+                Err(Error::UnknownTier(String::from("pit")))
+            );
+            // Nothing is repriced if a tier does not exist.
+            assert_eq!(erc20.price_of_seat(String::from("A1")), Some(20));
+            assert_eq!(
+                erc20.set_prices_batch(vec![
+                    (String::from("VIP"), 30),
+                    (String::from("balcony"), 8),
+                ]),
+                Ok(())
+            );
+            assert_eq!(erc20.price_of_seat(String::from("A1")), Some(30));
+            assert_eq!(erc20.price_of_seat(String::from("A2")), Some(8));
+
+            set_sender(accounts.bob, 0);
+            assert_eq!(
+                erc20.set_prices_batch(vec![(String::from("VIP"), 1)]),
+                Err(Error::NotOwner)
             );
         }
 
         #[ink::test]
-        fn transfer_from_works() {
-            // Constructor works.
-            let mut erc20 = Erc20::new(100);
-            // Transfer event triggered during initial construction.
+        fn price_and_fee_setters_need_admin_confirmations() {
+            let mut erc20 = seated_erc20();
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
 
-            // Bob fails to transfer tokens owned by Alice.
+            assert_eq!(erc20.set_tier(String::from("VIP"), 20, seats(&["A1"])), Ok(()));
+            assert_eq!(erc20.set_admins(vec![accounts.alice, accounts.bob], 2), Ok(()));
             assert_eq!(
-                erc20.transfer_from(accounts.alice, accounts.eve, 10),
-                Err(Error::InsufficientAllowance)
+                erc20.set_service_fee(500, Some(accounts.django)),
+                Err(Error::MultisigRequired)
+            );
+            assert_eq!(
+                erc20.set_tier_price(String::from("VIP"), 30),
+                Err(Error::MultisigRequired)
             );
-            // Alice approves Bob for token transfers on her behalf.
-            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
 
-            // The approve event takes place.
-            assert_eq!(ink_env::test::recorded_events().count(), 2);
+            let fee = erc20
+                .propose_action(AdminAction::SetServiceFee {
+                    fee_bps: 500,
+                    collector: Some(accounts.django),
+                })
+                .expect("Cannot propose");
+            let price = erc20
+                .propose_action(AdminAction::SetTierPrice {
+                    tier: String::from("VIP"),
+                    price: 30,
+                })
+                .expect("Cannot propose");
+            assert_eq!(erc20.service_fee(), (0, None));
 
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>();
-            // Create call.
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller.
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
-            );
+            set_sender(accounts.bob, 0);
+            assert_eq!(erc20.confirm_action(fee), Ok(()));
+            assert_eq!(erc20.confirm_action(price), Ok(()));
+            assert_eq!(erc20.service_fee(), (500, Some(accounts.django)));
+            assert_eq!(erc20.price_of_seat(String::from("A1")), Some(30));
+        }
 
-            // Bob transfers tokens from Alice to Eve.
+        #[ink::test]
+        fn finalizing_only_sweeps_vested_proceeds() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.bob, 10);
             assert_eq!(
-                erc20.transfer_from(accounts.alice, accounts.eve, 10),
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
                 Ok(())
             );
-            // Eve owns tokens.
-            assert_eq!(erc20.balance_of(accounts.eve), 10);
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_vesting(10, 50), Ok(()));
+            assert_eq!(erc20.set_event_end(now), Ok(()));
+            assert_eq!(erc20.finalize_event(true), Err(Error::FundsOutstanding));
 
-            // Check all transfer events that happened during the previous calls:
-            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 3);
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
-            // The second event `emitted_events[1]` is an Approve event that we skip checking.
-            assert_transfer_event(
-                &emitted_events[2],
-                Some(AccountId::from([0x01; 32])),
-                Some(AccountId::from([0x05; 32])),
-                10,
-            );
+            advance_time(10);
+            // Half of the proceeds has vested, the rest stays in the contract.
+            assert_eq!(erc20.finalize_event(true), Err(Error::FundsOutstanding));
+            assert_eq!(erc20.finalize_event(false), Ok(()));
+            assert!(erc20.is_finalized());
+            assert_eq!(erc20.withdrawable_balance(), 5);
         }
 
         #[ink::test]
-        fn allowance_must_not_change_on_failed_transfer() {
-            let mut erc20 = Erc20::new(100);
+        fn finalizing_under_a_timelock_sweeps_the_matured_withdrawal() {
+            let mut erc20 = seated_erc20();
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>();
 
-            // Alice approves Bob for token transfers on her behalf.
-            let alice_balance = erc20.balance_of(accounts.alice);
-            let initial_allowance = alice_balance + 2;
-            assert_eq!(erc20.approve(accounts.bob, initial_allowance), Ok(()));
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
+            );
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract, 10)
+                .expect("Cannot set account balance");
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_withdraw_delay(2), Ok(()));
+            assert_eq!(erc20.set_event_end(now), Ok(()));
+            assert_eq!(erc20.request_withdraw(4), Ok(()));
+            assert_eq!(erc20.finalize_event(true), Err(Error::FundsOutstanding));
 
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>();
-            // Create call.
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // balance_of
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller.
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(erc20.finalize_event(true), Err(Error::FundsOutstanding));
+            assert_eq!(erc20.finalize_event(false), Ok(()));
+            assert_eq!(erc20.pending_withdrawal(), None);
+            assert_eq!(erc20.withdrawable_balance(), 6);
+        }
+
+        #[ink::test]
+        fn finalizing_leaves_streamed_proceeds_to_the_stream() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>();
+
+            set_sender(accounts.bob, 10);
+            assert_eq!(
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
             );
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.set_withdraw_delay(1), Ok(()));
+            assert_eq!(erc20.request_stream(Some((accounts.django, 0))), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(erc20.execute_stream_change(), Ok(()));
+            assert_eq!(erc20.set_event_end(now), Ok(()));
 
-            // Bob tries to transfer tokens from Alice to Eve.
-            let emitted_events_before = ink_env::test::recorded_events();
+            assert_eq!(erc20.finalize_event(true), Err(Error::FundsOutstanding));
+            assert_eq!(erc20.finalize_event(false), Ok(()));
+            assert_eq!(erc20.withdrawable_balance(), 10);
+        }
+
+        #[ink::test]
+        fn reentrant_calls_are_rejected() {
+            let mut erc20 = seated_erc20();
+
+            // A call back into the contract sees the guard of the outer message.
+            ink_env::set_contract_storage(&Key::from(REENTRANCY_GUARD_KEY), &true);
+            assert_eq!(erc20.clear(), Err(Error::ReentrantCall));
+            ink_env::clear_contract_storage(&Key::from(REENTRANCY_GUARD_KEY));
+            assert_eq!(erc20.clear(), Ok(()));
+        }
+
+        #[ink::test]
+        fn only_verifiers_can_burn() {
+            let mut erc20 = seated_erc20();
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            set_sender(accounts.bob, 10);
             assert_eq!(
-                erc20.transfer_from(accounts.alice, accounts.eve, alice_balance + 1),
-                Err(Error::InsufficientBalance)
+                erc20.purchase_tickets(accounts.bob, 1, vec![1], seats(&["A1"]), None),
+                Ok(())
             );
-            // Allowance must have stayed the same
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.burn(accounts.bob, 1, BurnReason::Entry), Err(Error::NotVerifier));
+            assert_eq!(erc20.add_verifier(accounts.charlie), Err(Error::NotOwner));
+
+            set_sender(accounts.alice, 0);
+            assert_eq!(erc20.add_verifier(accounts.charlie), Ok(()));
+            set_sender(accounts.charlie, 0);
+            assert_eq!(erc20.burn(accounts.bob, 1, BurnReason::Entry), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.redeemed_count(), 1);
+            assert_eq!(erc20.outstanding_supply(), 2);
+            let burned = ink_env::test::recorded_events().last().expect("no event emitted");
+            let decoded_event = <Event as scale::Decode>::decode(&mut &burned.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Burned(Burned { from, value, reason }) = decoded_event {
+                assert_eq!(from, accounts.bob);
+                assert_eq!(value, 1);
+                assert_eq!(reason, BurnReason::Entry);
+            } else {
+                panic!("encountered unexpected event kind: expected a Burned event")
+            }
+        }
+
+        /// Example of a venue-management contract selling and admitting tickets of any
+        /// `TicketSale` deployment.
+        ///
+        /// On-chain it holds an `Erc20Ref`, see `box_office_at`. Purchases made through
+        /// the trait forward no value, so the box office has to be paid for with a
+        /// voucher or use the call builder of the reference to transfer the price.
+        struct BoxOffice<T: TicketSale> {
+            event: T,
+        }
+
+        impl<T: TicketSale> BoxOffice<T> {
+            /// Sells `seats` to `to` if they are all available.
+            fn sell(&mut self, to: AccountId, seats: Vec<String>) -> Result<()> {
+                if !self.event.is_seat_available(seats.clone()) {
+                    return Err(Error::SeatsUnavailable(seats))
+                }
+                self.event.purchase_tickets(to, seats.len() as Balance, Vec::new(), seats, None)
+            }
+
+            /// Admits the holder of `seat` to `session_id`, returning who was admitted.
+            fn admit(&mut self, seat: String, session_id: u32) -> Result<AccountId> {
+                let holder = self
+                    .event
+                    .seat_owner(seat.clone())
+                    .ok_or_else(|| Error::SeatNotSold(seat.clone()))?;
+                self.event.check_in(seat, session_id)?;
+                Ok(holder)
+            }
+        }
+
+        /// Returns a box office for the deployment at `event`.
+        ///
+        /// Unused, as the off-chain environment cannot dispatch calls to other contracts
+        /// and the tests drive the contract directly instead.
+        #[allow(dead_code)]
+        fn box_office_at(event: AccountId) -> BoxOffice<Erc20Ref> {
+            BoxOffice {
+                event: <Erc20Ref as ink_env::call::FromAccountId<ink_env::DefaultEnvironment>>::from_account_id(event),
+            }
+        }
+
+        #[ink::test]
+        fn box_office_sells_and_admits() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut box_office = BoxOffice {
+                event: seated_erc20(),
+            };
+            set_sender(accounts.alice, 0);
+            assert_eq!(box_office.event.add_verifier(accounts.django), Ok(()));
+
+            set_sender(accounts.django, 20);
+            assert_eq!(box_office.sell(accounts.bob, seats(&["A1", "A2"])), Ok(()));
             assert_eq!(
-                erc20.allowance(accounts.alice, accounts.bob),
-                initial_allowance
+                box_office.sell(accounts.charlie, seats(&["A2"])),
+                Err(Error::SeatsUnavailable(seats(&["A2"])))
             );
-            // No more events must have been emitted
-            let emitted_events_after = ink_env::test::recorded_events();
-            assert_eq!(emitted_events_before.count(), emitted_events_after.count());
+            assert_eq!(box_office.event.seat_owner(String::from("A1")), Some(accounts.bob));
+            assert_eq!(box_office.event.total_paid(accounts.django), 20);
+
+            set_sender(accounts.django, 0);
+            assert_eq!(box_office.admit(String::from("A1"), 0), Ok(accounts.bob));
+            assert_eq!(box_office.admit(String::from("A3"), 0), Err(Error::SeatNotSold(String::from("A3"))));
         }
     }
 
@@ -749,11 +11083,9 @@ mod erc20 {
                     prefix: b"Erc20::Transfer::to",
                     value: &expected_to,
                 }),
-                encoded_into_hash(&PrefixedValue {
-                    prefix: b"Erc20::Transfer::value",
-                    value: &expected_value,
-                }),
             ];
+            // `value` is event data, only `from` and `to` are topics.
+            assert_eq!(event.topics.len(), expected_topics.len(), "encountered invalid topic count");
 
             let topics = event.topics.clone();
             for (n, (actual_topic, expected_topic)) in
@@ -771,15 +11103,30 @@ mod erc20 {
             }
         }
 
+        fn assert_deployed_event(
+            event: &ink_env::test::EmittedEvent,
+            expected_deployer: AccountId,
+            expected_owner: AccountId,
+        ) {
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Deployed(Deployed { deployer, owner }) = decoded_event {
+                assert_eq!(deployer, expected_deployer, "encountered invalid Deployed.deployer");
+                assert_eq!(owner, expected_owner, "encountered invalid Deployed.owner");
+            } else {
+                panic!("encountered unexpected event kind: expected a Deployed event")
+            }
+        }
+
         /// The default constructor does its job.
         #[ink::test]
         fn new_works() {
             // Constructor works.
-            let _erc20 = Erc20::new(100);
+            let _erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
 
-            // Transfer event triggered during initial construction.
+            // Transfer and Deployed events triggered during initial construction.
             let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(1, emitted_events.len());
+            assert_eq!(2, emitted_events.len());
 
             assert_transfer_event(
                 &emitted_events[0],
@@ -787,13 +11134,18 @@ mod erc20 {
                 Some(AccountId::from([0x01; 32])),
                 100,
             );
+            assert_deployed_event(
+                &emitted_events[1],
+                AccountId::from([0x01; 32]),
+                AccountId::from([0x01; 32]),
+            );
         }
 
         /// The total supply was applied.
         #[ink::test]
         fn total_supply_works() {
             // Constructor works.
-            let erc20 = Erc20::new(100);
+            let erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
             // Transfer event triggered during initial construction.
             let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
             assert_transfer_event(
@@ -810,7 +11162,7 @@ mod erc20 {
         #[ink::test]
         fn balance_of_works() {
             // Constructor works
-            let erc20 = Erc20::new(100);
+            let erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
             // Transfer event triggered during initial construction
             let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
             assert_transfer_event(
@@ -830,7 +11182,7 @@ mod erc20 {
         #[ink::test]
         fn transfer_works() {
             // Constructor works.
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
             // Transfer event triggered during initial construction.
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
@@ -842,7 +11194,7 @@ mod erc20 {
             assert_eq!(erc20.balance_of(accounts.bob), 10);
 
             let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 2);
+            assert_eq!(emitted_events.len(), 3);
             // Check first transfer event related to ERC-20 instantiation.
             assert_transfer_event(
                 &emitted_events[0],
@@ -850,9 +11202,10 @@ mod erc20 {
                 Some(AccountId::from([0x01; 32])),
                 100,
             );
+            // The second event `emitted_events[1]` is the Deployed event.
             // Check the second transfer event relating to the actual trasfer.
             assert_transfer_event(
-                &emitted_events[1],
+                &emitted_events[2],
                 Some(AccountId::from([0x01; 32])),
                 Some(AccountId::from([0x02; 32])),
                 10,
@@ -862,7 +11215,7 @@ mod erc20 {
         #[ink::test]
         fn invalid_transfer_should_fail() {
             // Constructor works.
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
 
@@ -883,9 +11236,9 @@ mod erc20 {
             assert_eq!(erc20.balance_of(accounts.bob), 0);
             assert_eq!(erc20.balance_of(accounts.eve), 0);
 
-            // Transfer event triggered during initial construction.
+            // Transfer and Deployed events triggered during initial construction.
             let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 1);
+            assert_eq!(emitted_events.len(), 2);
             assert_transfer_event(
                 &emitted_events[0],
                 None,
@@ -897,7 +11250,7 @@ mod erc20 {
         #[ink::test]
         fn transfer_from_works() {
             // Constructor works.
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
             // Transfer event triggered during initial construction.
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
@@ -911,7 +11264,7 @@ mod erc20 {
             assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
 
             // The approve event takes place.
-            assert_eq!(ink_env::test::recorded_events().count(), 2);
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
 
             // Set the contract as callee and Bob as caller.
             let contract = ink_env::account_id::<ink_env::DefaultEnvironment>();
@@ -928,16 +11281,17 @@ mod erc20 {
 
             // Check all transfer events that happened during the previous calls:
             let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 3);
+            assert_eq!(emitted_events.len(), 4);
             assert_transfer_event(
                 &emitted_events[0],
                 None,
                 Some(AccountId::from([0x01; 32])),
                 100,
             );
-            // The second event `emitted_events[1]` is an Approve event that we skip checking.
+            // `emitted_events[1]` is the Deployed event and `emitted_events[2]` an Approve
+            // event that we skip checking.
             assert_transfer_event(
-                &emitted_events[2],
+                &emitted_events[3],
                 Some(AccountId::from([0x01; 32])),
                 Some(AccountId::from([0x05; 32])),
                 10,
@@ -946,7 +11300,7 @@ mod erc20 {
 
         #[ink::test]
         fn allowance_must_not_change_on_failed_transfer() {
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, 1, AccountId::from([0x01; 32]), Vec::new());
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
 